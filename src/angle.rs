@@ -3,10 +3,12 @@ use std::f32::{
     EPSILON,
 };
 
+use crate::{CrissCrossError, CrissCrossResult};
+
 const DEG_90: f32 = PI * 0.5;
 const DEG_270: f32 = PI * 1.5;
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct AngleRad(pub f32);
 
 impl AngleRad {
@@ -42,6 +44,91 @@ impl From<f32> for AngleRad {
     }
 }
 
+/// Unit vector pointing in the direction of a ray.
+///
+/// Rays are stepped via its components, thus exactly horizontal or vertical vectors like
+/// `(0.0, 1.0)` result in exactly horizontal or vertical rays.
+/// When converting an angle components within rounding error of `0.0` are snapped onto it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Direction {
+    x: f32,
+    y: f32,
+}
+
+impl Direction {
+    /// Direction of the vector `(dx, dy)`, e.g. a velocity, which doesn't need to be
+    /// normalized.
+    #[must_use]
+    pub fn new(dx: f32, dy: f32) -> Self {
+        // Scale down first so that the length of large vectors doesn't overflow
+        let scale = dx.abs().max(dy.abs());
+        let (dx, dy) = (dx / scale, dy / scale);
+        let length = dx.hypot(dy);
+        Self {
+            x: dx / length,
+            y: dy / length,
+        }
+    }
+
+    pub const fn x(&self) -> f32 {
+        self.x
+    }
+
+    pub const fn y(&self) -> f32 {
+        self.y
+    }
+
+    /// Angle of the direction in `0.0..TAU`.
+    pub fn angle(&self) -> AngleRad {
+        AngleRad(self.y.atan2(self.x)).clamp()
+    }
+
+    /// Returns `false` for directions obtained from zero length or non-finite vectors as well
+    /// as non-finite angles.
+    pub fn is_valid(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && (self.x != 0.0 || self.y != 0.0)
+    }
+}
+
+impl From<&AngleRad> for Direction {
+    #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
+    fn from(angle: &AngleRad) -> Self {
+        let (sin, cos) = f64::from(angle.clamp().0).sin_cos();
+        let epsilon = f64::from(f32::EPSILON);
+        // Snap axis aligned and diagonal angles onto their exact directions to make up for the
+        // rounding error of the angle itself
+        let (sin, cos) = if (sin.abs() - cos.abs()).abs() < epsilon {
+            let half = std::f64::consts::FRAC_1_SQRT_2;
+            (half.copysign(sin), half.copysign(cos))
+        } else {
+            (sin, cos)
+        };
+        let snapped = |c: f64| if c.abs() < epsilon { 0.0 } else { c as f32 };
+        Self {
+            x: snapped(cos),
+            y: snapped(sin),
+        }
+    }
+}
+
+impl From<AngleRad> for Direction {
+    fn from(angle: AngleRad) -> Self {
+        Self::from(&angle)
+    }
+}
+
+impl From<f32> for Direction {
+    fn from(radians: f32) -> Self {
+        Self::from(&AngleRad(radians))
+    }
+}
+
+impl From<(f32, f32)> for Direction {
+    fn from((dx, dy): (f32, f32)) -> Self {
+        Self::new(dx, dy)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum DirectionX {
     Left,
@@ -74,3 +161,50 @@ impl From<&AngleRad> for DirectionY {
         }
     }
 }
+
+impl From<&Direction> for DirectionX {
+    fn from(direction: &Direction) -> Self {
+        match direction.x {
+            x if x > 0.0 => Self::Right,
+            x if x < 0.0 => Self::Left,
+            _ => Self::Parallel,
+        }
+    }
+}
+
+impl From<&Direction> for DirectionY {
+    fn from(direction: &Direction) -> Self {
+        match direction.y {
+            y if y > 0.0 => Self::Up,
+            y if y < 0.0 => Self::Down,
+            _ => Self::Parallel,
+        }
+    }
+}
+
+pub fn validated_angle<T: Into<AngleRad>>(angle: T) -> CrissCrossResult<AngleRad> {
+    let angle = angle.into();
+    if angle.0.is_finite() {
+        Ok(angle)
+    } else {
+        Err(CrissCrossError::NonFiniteAngle(angle.0))
+    }
+}
+
+pub fn validated_half_angle<T: Into<AngleRad>>(half_angle: T) -> CrissCrossResult<AngleRad> {
+    let half_angle = half_angle.into();
+    if (0.0..=PI).contains(&half_angle.0) {
+        Ok(half_angle)
+    } else {
+        Err(CrissCrossError::InvalidHalfAngle(half_angle.0))
+    }
+}
+
+pub fn validated_direction<T: Into<Direction>>(direction: T) -> CrissCrossResult<Direction> {
+    let direction = direction.into();
+    if direction.is_valid() {
+        Ok(direction)
+    } else {
+        Err(CrissCrossError::InvalidDirection)
+    }
+}
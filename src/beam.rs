@@ -1,6 +1,6 @@
-use std::fmt;
+use std::{cmp::Ordering, fmt};
 
-use crate::{ray::Ray, ray_iter::RayIter, TilePosition};
+use crate::{ray::Ray, ray_hit::RayHit, ray_iter::RayHitIter, BitGrid, Grid, TilePosition};
 
 #[derive(PartialEq)]
 pub struct BeamIntersect(pub usize, pub TilePosition);
@@ -11,17 +11,27 @@ impl fmt::Debug for BeamIntersect {
     }
 }
 
+/// Like [`BeamIntersect`] including the full [`RayHit`] of the ray with the given index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeamHit(pub usize, pub RayHit);
+
+impl From<BeamHit> for BeamIntersect {
+    fn from(BeamHit(idx, hit): BeamHit) -> Self {
+        Self(idx, hit.tp)
+    }
+}
+
 pub struct Beam {
-    rays: Vec<RayIter>,
-    ray_origins: Vec<TilePosition>,
-    tile_size: f32,
-    intersects: Vec<Option<TilePosition>>,
+    rays: Vec<RayHitIter>,
+    intersects: Vec<Option<RayHit>>,
+    // Tiles entered so far, if each tile should only be yielded once
+    visited: Option<BitGrid>,
 }
 
 impl Beam {
-    pub(crate) fn new(tile_size: f32, rays: Vec<Ray>) -> Self {
-        let ray_origins = rays.iter().map(|ray| ray.tp.clone()).collect();
-        let mut rays: Vec<RayIter> = rays.into_iter().map(IntoIterator::into_iter).collect();
+    pub(crate) fn new(rays: Vec<Ray>) -> Self {
+        let mut rays: Vec<RayHitIter> =
+            rays.into_iter().map(|ray| ray.into_iter().hits()).collect();
 
         let mut intersects = vec![None; rays.len()];
         for (idx, ray) in rays.iter_mut().enumerate() {
@@ -31,25 +41,64 @@ impl Beam {
         }
         Self {
             rays,
-            ray_origins,
-            tile_size,
             intersects,
+            visited: None,
+        }
+    }
+
+    /// Only yields the first intersection with each tile, i.e. for rays that diverge and thus
+    /// enter the same tiles one after the other.
+    pub(crate) fn with_unique_tiles(mut self, grid: &Grid) -> Self {
+        self.visited = Some(BitGrid::new(grid));
+        self
+    }
+
+    pub(crate) fn next_intersect(&mut self) -> Option<BeamHit> {
+        loop {
+            let BeamHit(idx, hit) = self.next_hits()?.into_iter().next()?;
+            if let Some(visited) = &mut self.visited {
+                if visited.get(hit.tp.x, hit.tp.y) {
+                    continue;
+                }
+                visited.set(hit.tp.x, hit.tp.y, true);
+            }
+            return Some(BeamHit(idx, hit));
         }
     }
 
-    pub(crate) fn next_intersect(&mut self) -> Option<BeamIntersect> {
-        let BeamIntersect(idx, tp) = self.closest_intersect()?;
-        self.update_intersects(&tp);
-        Some(BeamIntersect(idx, tp))
+    /// Hits of all rays that enter the closest tile next, starting with the closest one.
+    /// Each of those rays is advanced to its next hit.
+    pub(crate) fn next_hits(&mut self) -> Option<Vec<BeamHit>> {
+        let BeamHit(_, closest) = self.closest_intersect()?;
+        let mut hits = self.update_intersects(&closest.tp);
+        hits.sort_by(|BeamHit(_, hit1), BeamHit(_, hit2)| {
+            hit1.distance
+                .partial_cmp(&hit2.distance)
+                .unwrap_or(Ordering::Equal)
+        });
+        Some(hits)
     }
 
-    fn update_intersects(&mut self, intersect_tp: &TilePosition) {
+    /// Stops the ray with the given index, i.e. none of its remaining hits are yielded.
+    pub(crate) fn stop_ray(&mut self, idx: usize) {
+        if let Some(slot) = self.intersects.get_mut(idx) {
+            *slot = None;
+        }
+    }
+
+    pub(crate) fn ray_count(&self) -> usize {
+        self.rays.len()
+    }
+
+    // The indexes need to be collected since the intersects are updated while iterating them
+    #[allow(clippy::needless_collect)]
+    fn update_intersects(&mut self, intersect_tp: &TilePosition) -> Vec<BeamHit> {
         let idxs_with_identical_xy: Vec<usize> = self
             .intersects
             .iter()
             .enumerate()
-            .filter_map(|(idx, tp)| {
-                if tp.as_ref()?.is_same_tile(intersect_tp) {
+            .filter_map(|(idx, hit)| {
+                if hit.as_ref()?.tp.is_same_tile(intersect_tp) {
                     Some(idx)
                 } else {
                     None
@@ -57,33 +106,30 @@ impl Beam {
             })
             .collect();
 
-        for idx in idxs_with_identical_xy {
-            // SAFETY we got indexes by iterating intersects above and know that rays have
-            // same length (see `Beam::new`).
-            let slot = unsafe { self.intersects.get_unchecked_mut(idx) };
-            let ray = unsafe { self.rays.get_unchecked_mut(idx) };
-            *slot = ray.next();
-        }
+        idxs_with_identical_xy
+            .into_iter()
+            .filter_map(|idx| {
+                // SAFETY we got indexes by iterating intersects above and know that rays have
+                // same length (see `Beam::new`).
+                let slot = unsafe { self.intersects.get_unchecked_mut(idx) };
+                let ray = unsafe { self.rays.get_unchecked_mut(idx) };
+                let hit = std::mem::replace(slot, ray.next())?;
+                Some(BeamHit(idx, hit))
+            })
+            .collect()
     }
 
-    fn closest_intersect(&self) -> Option<BeamIntersect> {
-        #[allow(clippy::filter_map, clippy::unwrap_used)]
-        let (idx, tp, _) = self
+    fn closest_intersect(&self) -> Option<BeamHit> {
+        let (idx, hit) = self
             .intersects
             .iter()
             .enumerate()
-            .filter(|(_, tp)| tp.is_some())
-            .map(|(idx, tp)| {
-                // SAFETY we setup ray_origins and intersects by mapping over rays
-                // therefore they have the same amount of items
-                let orig = unsafe { self.ray_origins.get_unchecked(idx) };
-                (idx, tp.as_ref().unwrap(), orig)
-            })
-            .min_by(|(_, tp1, orig1), (_, tp2, orig2)| {
-                let dist1 = orig1.distance_global(*tp1, self.tile_size);
-                let dist2 = orig2.distance_global(*tp2, self.tile_size);
-                dist1.partial_cmp(&dist2).unwrap()
+            .filter_map(|(idx, hit)| Some((idx, hit.as_ref()?)))
+            .min_by(|(_, hit1), (_, hit2)| {
+                hit1.distance
+                    .partial_cmp(&hit2.distance)
+                    .unwrap_or(Ordering::Equal)
             })?;
-        Some(BeamIntersect(idx, tp.clone()))
+        Some(BeamHit(idx, hit.clone()))
     }
 }
@@ -1,23 +1,150 @@
-use crate::beam::{Beam, BeamIntersect};
+use crate::{
+    beam::{Beam, BeamHit, BeamIntersect},
+    BeamCrossing, Crossing,
+};
 
 pub struct BeamIter {
+    hits: BeamHitIter,
+}
+
+/// Iterates over the hits of all rays of a beam, see [`BeamIter::hits`].
+pub struct BeamHitIter {
     beam: Beam,
 }
 
 impl Beam {
     const fn iter(self) -> BeamIter {
-        BeamIter { beam: self }
+        BeamIter {
+            hits: BeamHitIter { beam: self },
+        }
+    }
+}
+
+impl BeamIter {
+    /// Converts this iterator into one that yields the full [`crate::RayHit`] for each
+    /// intersection.
+    pub fn hits(self) -> BeamHitIter {
+        self.hits
+    }
+}
+
+impl BeamIter {
+    /// Stops each ray of the beam on its own before the first tile for which `is_valid` returns
+    /// `false` while the remaining rays keep going, see [`OccludedBeamIter`].
+    pub fn occluded_by<P>(self, is_valid: P) -> OccludedBeamIter<P>
+    where
+        P: FnMut(&BeamIntersect) -> bool,
+    {
+        let beam = self.hits.beam;
+        let crossings = (0..beam.ray_count()).map(|_| Crossing::default()).collect();
+        OccludedBeamIter {
+            beam,
+            is_valid,
+            crossings,
+            nearest: None,
+        }
     }
 }
 
 impl Iterator for BeamIter {
     type Item = BeamIntersect;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        self.hits.next().map(BeamIntersect::from)
+    }
+}
+
+impl Iterator for BeamHitIter {
+    type Item = BeamHit;
+
     fn next(&mut self) -> Option<Self::Item> {
         self.beam.next_intersect()
     }
 }
 
+/// Iterates over the intersections of a beam whose rays each stop at the first invalid tile
+/// they enter, e.g. a light beam partially occluded by a pillar.
+///
+/// Tracks the last valid and first invalid position of each ray, see
+/// [`OccludedBeamIter::crossings`].
+pub struct OccludedBeamIter<P> {
+    beam: Beam,
+    is_valid: P,
+    crossings: Vec<Crossing>,
+    nearest: Option<BeamHit>,
+}
+
+impl<P> OccludedBeamIter<P>
+where
+    P: FnMut(&BeamIntersect) -> bool,
+{
+    /// Last valid and first invalid position of each ray, indexed like the yielded
+    /// [`BeamIntersect`]s.
+    /// Unlike [`crate::TileRaycaster::crossing`] the invalid position is included even if the
+    /// very first tile a ray enters is invalid.
+    /// Only complete once the iterator is exhausted, see
+    /// [`OccludedBeamIter::into_beam_crossing`].
+    pub fn crossings(&self) -> &[Crossing] {
+        &self.crossings
+    }
+
+    /// Closest invalid position any ray entered so far.
+    pub const fn nearest(&self) -> Option<&BeamHit> {
+        self.nearest.as_ref()
+    }
+
+    /// Exhausts the iterator and returns the [`Crossing`] of each ray together with the
+    /// closest invalid position.
+    pub fn into_beam_crossing(mut self) -> BeamCrossing {
+        self.by_ref().for_each(drop);
+        BeamCrossing {
+            crossings: self.crossings,
+            nearest: self.nearest,
+        }
+    }
+}
+
+impl<P> Iterator for OccludedBeamIter<P>
+where
+    P: FnMut(&BeamIntersect) -> bool,
+{
+    type Item = BeamIntersect;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut next = None;
+            for hit in self.beam.next_hits()? {
+                let BeamHit(idx, ray_hit) = &hit;
+                let intersect = BeamIntersect(*idx, ray_hit.tp.clone());
+                let tp = &ray_hit.tp;
+                let valid = (self.is_valid)(&intersect);
+                if let Some(crossing) = self.crossings.get_mut(*idx) {
+                    if valid {
+                        crossing.valid = Some(tp.clone());
+                    } else {
+                        crossing.invalid = Some(tp.clone());
+                    }
+                }
+                if valid {
+                    next.get_or_insert(intersect);
+                } else {
+                    self.beam.stop_ray(*idx);
+                    let is_nearer = self.nearest.as_ref().map_or(true, |BeamHit(_, nearest)| {
+                        ray_hit.distance < nearest.distance
+                    });
+                    if is_nearer {
+                        self.nearest = Some(hit);
+                    }
+                }
+            }
+            // Keep going if all rays entering this tile were stopped by it
+            if next.is_some() {
+                return next;
+            }
+        }
+    }
+}
+
 impl IntoIterator for Beam {
     type Item = BeamIntersect;
     type IntoIter = BeamIter;
@@ -0,0 +1,129 @@
+use crate::{ray::Ray, ray_hit::RayHit, AngleRad, Grid, TileEdge, TilePosition};
+
+/// Pose and field of view of a first-person camera.
+#[derive(Debug, Clone)]
+pub struct Camera {
+    pub position: TilePosition,
+    /// Direction the camera is looking at.
+    pub heading: AngleRad,
+    /// Horizontal field of view.
+    pub fov: AngleRad,
+}
+
+impl Camera {
+    #[must_use]
+    pub fn new<H: Into<AngleRad>, F: Into<AngleRad>>(
+        position: TilePosition,
+        heading: H,
+        fov: F,
+    ) -> Self {
+        Self {
+            position,
+            heading: heading.into(),
+            fov: fov.into(),
+        }
+    }
+
+    /// Distance of the projection plane from the camera in pixels for a screen of the given
+    /// width, assuming square pixels.
+    #[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+    pub fn projection_distance(&self, screen_width: u32) -> f32 {
+        (screen_width as f32 / 2.0) / (self.fov.0 / 2.0).tan()
+    }
+
+    /// Angle of the ray cast for the given screen column, column `0` being the leftmost.
+    #[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+    pub fn column_angle(&self, column: u32, screen_width: u32) -> AngleRad {
+        // Offset on the camera plane ranging from 1.0 (left) to -1.0 (right)
+        let plane_x = 1.0 - 2.0 * (column as f32 + 0.5) / screen_width as f32;
+        let offset = (plane_x * (self.fov.0 / 2.0).tan()).atan();
+        AngleRad(self.heading.0 + offset)
+    }
+}
+
+/// Wall hit by the ray cast for a single screen column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnHit {
+    /// Position at which the ray entered the wall tile.
+    pub tp: TilePosition,
+    /// Face of the wall tile that was hit.
+    pub edge: TileEdge,
+    /// Distance from the camera in world units.
+    pub distance: f32,
+    /// Distance projected onto the camera heading which corrects the fish-eye effect.
+    pub perpendicular_distance: f32,
+    /// Horizontal texture coordinate in `0.0..=1.0` running from left to right as seen by the
+    /// camera.
+    pub texture_u: f32,
+    /// Height in pixels of a wall that is as high as a tile is wide.
+    pub wall_height: f32,
+}
+
+/// Result of casting the ray for a single screen column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    /// Angle of the ray in radians.
+    pub angle: f32,
+    /// `None` if the ray left the grid without hitting a wall.
+    pub hit: Option<ColumnHit>,
+}
+
+/// Casts one ray per screen column from the camera and reports the first tile for which
+/// `is_blocking` returns `true` for each of them.
+pub fn cast_columns<P>(
+    grid: &Grid,
+    camera: &Camera,
+    screen_width: u32,
+    mut is_blocking: P,
+) -> Vec<Column>
+where
+    P: FnMut(&TilePosition) -> bool,
+{
+    let projection_distance = camera.projection_distance(screen_width);
+    (0..screen_width)
+        .map(|column| {
+            let angle = camera.column_angle(column, screen_width).clamp();
+            let hit = Ray::new(grid.clone(), camera.position.clone(), angle.clone())
+                .into_iter()
+                .hits()
+                .find(|hit| is_blocking(&hit.tp))
+                .and_then(|hit| column_hit(grid, camera, &angle, projection_distance, hit));
+            Column {
+                angle: angle.0,
+                hit,
+            }
+        })
+        .collect()
+}
+
+fn column_hit(
+    grid: &Grid,
+    camera: &Camera,
+    angle: &AngleRad,
+    projection_distance: f32,
+    RayHit {
+        tp, edge, distance, ..
+    }: RayHit,
+) -> Option<ColumnHit> {
+    let edge = edge?;
+    let tile_size = grid.tile_size;
+    let perpendicular_distance = distance * (angle.0 - camera.heading.0).cos();
+    let texture_u = match edge {
+        TileEdge::Left => 1.0 - tp.rel_y / tile_size.height,
+        TileEdge::Right => tp.rel_y / tile_size.height,
+        TileEdge::Bottom => tp.rel_x / tile_size.width,
+        TileEdge::Top => 1.0 - tp.rel_x / tile_size.width,
+        TileEdge::BottomLeft | TileEdge::BottomRight | TileEdge::TopLeft | TileEdge::TopRight => {
+            0.0
+        }
+    };
+    let wall_height = tile_size.width / perpendicular_distance * projection_distance;
+    Some(ColumnHit {
+        tp,
+        edge,
+        distance,
+        perpendicular_distance,
+        texture_u,
+        wall_height,
+    })
+}
@@ -0,0 +1,77 @@
+use std::{error::Error, fmt};
+
+pub type CrissCrossResult<T> = Result<T, CrissCrossError>;
+
+/// Errors returned when inputs cannot be mapped onto a grid or are invalid otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CrissCrossError {
+    /// A position lies left of or below the grid, i.e. its tile coordinates would be negative.
+    NegativeTileCoordinates { x: i64, y: i64 },
+    /// World coordinates lie outside of the grid.
+    OutOfBounds { x: f32, y: f32 },
+    /// A tile position lies outside of the grid or its relative offset outside of its tile.
+    TileOutOfBounds { x: u32, y: u32 },
+    /// A hex position lies outside of the hex grid or its offset outside of its hex.
+    HexOutOfBounds { q: i32, r: i32 },
+    /// A voxel position lies outside of the voxel grid or its offset outside of its voxel.
+    VoxelOutOfBounds { x: u32, y: u32, z: u32 },
+    /// Width and height of tiles need to be positive and finite.
+    InvalidTileSize { width: f32, height: f32 },
+    /// Beam widths need to be positive and finite.
+    InvalidWidth(f32),
+    /// Angles need to be finite.
+    NonFiniteAngle(f32),
+    /// Direction vectors need to be finite and of non-zero length.
+    InvalidDirection,
+    /// Half-angles of cones need to be between `0` and `PI`.
+    InvalidHalfAngle(f32),
+    /// Maximum distances of rays need to be positive or zero.
+    InvalidDistance(f32),
+    /// World coordinates need to be finite.
+    NonFiniteCoordinates { x: f32, y: f32 },
+    /// Rays cast across a wrapping grid never leave it and thus need a maximum distance.
+    UnboundedRay,
+}
+
+impl fmt::Display for CrissCrossError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NegativeTileCoordinates { x, y } => {
+                write!(f, "tile coordinates ({x}, {y}) are off grid")
+            }
+            Self::OutOfBounds { x, y } => {
+                write!(f, "world coordinates ({x}, {y}) are outside of the grid")
+            }
+            Self::TileOutOfBounds { x, y } => {
+                write!(f, "tile position at ({x}, {y}) is outside of the grid")
+            }
+            Self::HexOutOfBounds { q, r } => {
+                write!(f, "hex position at ({q}, {r}) is outside of the grid")
+            }
+            Self::VoxelOutOfBounds { x, y, z } => {
+                write!(
+                    f,
+                    "voxel position at ({x}, {y}, {z}) is outside of the grid"
+                )
+            }
+            Self::InvalidTileSize { width, height } => {
+                write!(f, "tile size {width}x{height} needs to be positive")
+            }
+            Self::InvalidWidth(width) => write!(f, "width {width} needs to be positive"),
+            Self::NonFiniteAngle(angle) => write!(f, "angle {angle} is not finite"),
+            Self::InvalidDirection => write!(f, "direction needs to be finite and non-zero"),
+            Self::InvalidHalfAngle(angle) => {
+                write!(f, "half-angle {angle} needs to be between 0 and PI")
+            }
+            Self::InvalidDistance(distance) => {
+                write!(f, "distance {distance} needs to be positive or zero")
+            }
+            Self::NonFiniteCoordinates { x, y } => {
+                write!(f, "world coordinates ({x}, {y}) are not finite")
+            }
+            Self::UnboundedRay => write!(f, "rays cast across a wrapping grid need a range"),
+        }
+    }
+}
+
+impl Error for CrissCrossError {}
@@ -0,0 +1,425 @@
+use std::convert::TryFrom;
+
+use crate::{ray::Ray, ray_hit::RayHit, BitGrid, Grid, TilePosition, TileSize};
+
+/// Algorithm used to compute the tiles visible from an origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FovAlgorithm {
+    /// Casts rays from the exact origin towards the tiles surrounding it, see [`ray_cast_fov`].
+    #[default]
+    RayCasting,
+    /// Symmetric shadowcasting from the center of the origin tile, see [`shadowcast_fov`].
+    Shadowcasting,
+}
+
+pub fn field_of_view<P>(
+    grid: &Grid,
+    origin: &TilePosition,
+    radius: f32,
+    algorithm: FovAlgorithm,
+    is_blocking: P,
+) -> BitGrid
+where
+    P: FnMut(&TilePosition) -> bool,
+{
+    match algorithm {
+        FovAlgorithm::RayCasting => ray_cast_fov(grid, origin, radius, is_blocking),
+        FovAlgorithm::Shadowcasting => shadowcast_fov(grid, origin, radius, is_blocking),
+    }
+}
+
+/// Computes all tiles visible from the `origin` within `radius` (in world units) by casting a
+/// ray towards every tile on the perimeter of the square enclosing that radius.
+///
+/// Neighboring rays are never further apart than one tile inside the radius which ensures that
+/// no tile is skipped. A tile is considered inside the radius if its center is.
+/// Blocking tiles are visible themselves but hide everything behind them.
+pub fn ray_cast_fov<P>(
+    grid: &Grid,
+    origin: &TilePosition,
+    radius: f32,
+    mut is_blocking: P,
+) -> BitGrid
+where
+    P: FnMut(&TilePosition) -> bool,
+{
+    let tile_size = grid.tile_size;
+    let mut visible = BitGrid::new(grid);
+    visible.set(origin.x, origin.y, true);
+
+    let within_radius = |tp: &TilePosition| {
+        let center = TilePosition::new(tp.x, tp.y, tile_size.width / 2.0, tile_size.height / 2.0);
+        origin.distance_global(&center, tile_size) <= radius
+    };
+
+    // Visibility doesn't wrap around the edges of the grid
+    let bounded = Grid {
+        wrapping: false,
+        ..grid.clone()
+    };
+    for angle in perimeter_angles(grid, origin, radius) {
+        let ray = Ray::new(bounded.clone(), origin.clone(), angle);
+        for RayHit { tp, distance, .. } in ray.into_iter().hits() {
+            if distance > radius {
+                break;
+            }
+            if within_radius(&tp) {
+                visible.set(tp.x, tp.y, true);
+            }
+            if is_blocking(&tp) {
+                break;
+            }
+        }
+    }
+    visible
+}
+
+/// Angles from the origin towards the centers of all tiles on the perimeter of a square that
+/// encloses the `radius` around the origin's tile.
+#[allow(
+    clippy::integer_arithmetic,
+    clippy::as_conversions,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss
+)]
+fn perimeter_angles(grid: &Grid, origin: &TilePosition, radius: f32) -> Vec<f32> {
+    let TileSize { width, height } = grid.tile_size;
+    // One extra ring makes up for the origin not being located at the center of its tile
+    let half_side = (radius / grid.tile_size.min()).ceil().max(0.0) as i64 + 1;
+
+    let (ox, oy) = (i64::from(origin.x), i64::from(origin.y));
+    let origin_x = width.mul_add(origin.x as f32, origin.rel_x);
+    let origin_y = height.mul_add(origin.y as f32, origin.rel_y);
+
+    let angle_to = |(x, y): (i64, i64)| {
+        let center_x = width.mul_add(x as f32, width / 2.0);
+        let center_y = height.mul_add(y as f32, height / 2.0);
+        (center_y - origin_y).atan2(center_x - origin_x)
+    };
+
+    let (min_x, max_x) = (ox - half_side, ox + half_side);
+    let (min_y, max_y) = (oy - half_side, oy + half_side);
+    let bottom_top = (min_x..=max_x).flat_map(|x| vec![(x, min_y), (x, max_y)]);
+    let left_right = (min_y + 1..max_y).flat_map(|y| vec![(min_x, y), (max_x, y)]);
+    bottom_top.chain(left_right).map(angle_to).collect()
+}
+
+//
+// Symmetric Shadowcasting
+//
+
+/// Computes all tiles visible from the `origin` within `radius` (in world units) via
+/// symmetric shadowcasting, see <https://www.albertford.com/shadowcasting/>.
+///
+/// Only the tile of the origin is considered, i.e. all calculations are based on tile centers
+/// which guarantees that if tile A sees tile B then tile B sees tile A.
+/// Shadows are computed in tile space, i.e. for rectangular tiles they are only approximate
+/// while the radius is still honored in world units.
+/// A tile is considered inside the radius if its center is.
+/// Blocking tiles are visible themselves but hide everything behind them.
+/// The predicate is only invoked for tiles inside the grid and receives their center.
+#[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+pub fn shadowcast_fov<P>(
+    grid: &Grid,
+    origin: &TilePosition,
+    radius: f32,
+    mut is_blocking: P,
+) -> BitGrid
+where
+    P: FnMut(&TilePosition) -> bool,
+{
+    let mut visible = BitGrid::new(grid);
+    visible.set(origin.x, origin.y, true);
+
+    let max_depth = (radius / grid.tile_size.min()).floor().max(0.0) as i64;
+    let origin = (i64::from(origin.x), i64::from(origin.y));
+
+    for quadrant in &[
+        Quadrant::North,
+        Quadrant::East,
+        Quadrant::South,
+        Quadrant::West,
+    ] {
+        let mut rows = vec![Row::first()];
+        while let Some(mut row) = rows.pop() {
+            if row.depth > max_depth {
+                continue;
+            }
+            let mut previous_is_wall = None;
+            for col in row.min_col()..=row.max_col() {
+                let (x, y) = quadrant.transform(origin, row.depth, col);
+                #[allow(clippy::integer_arithmetic)]
+                let (dx, dy) = (x - origin.0, y - origin.1);
+                let tile = tile_center(grid, x, y);
+                let is_wall = tile.as_ref().map_or(true, &mut is_blocking);
+
+                if let Some(tp) = tile {
+                    let in_radius = within_radius(grid.tile_size, dx, dy, radius);
+                    if in_radius && (is_wall || row.is_symmetric(col)) {
+                        visible.set(tp.x, tp.y, true);
+                    }
+                }
+
+                match previous_is_wall {
+                    Some(true) if !is_wall => row.start_slope = Slope::of(row.depth, col),
+                    Some(false) if is_wall => {
+                        let mut next = row.next();
+                        next.end_slope = Slope::of(row.depth, col);
+                        rows.push(next);
+                    }
+                    Some(_) | None => {}
+                }
+                previous_is_wall = Some(is_wall);
+            }
+            if previous_is_wall == Some(false) {
+                rows.push(row.next());
+            }
+        }
+    }
+    visible
+}
+
+fn tile_center(grid: &Grid, x: i64, y: i64) -> Option<TilePosition> {
+    let x = u32::try_from(x).ok().filter(|x| *x < grid.cols)?;
+    let y = u32::try_from(y).ok().filter(|y| *y < grid.rows)?;
+    let TileSize { width, height } = grid.tile_size;
+    Some(TilePosition::new(x, y, width / 2.0, height / 2.0))
+}
+
+/// `dx` and `dy` are given in tiles and `radius` in world units.
+#[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+fn within_radius(tile_size: TileSize, dx: i64, dy: i64, radius: f32) -> bool {
+    (dx as f32 * tile_size.width).hypot(dy as f32 * tile_size.height) <= radius
+}
+
+enum Quadrant {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Quadrant {
+    /// Converts `depth` and `col` relative to the quadrant into tile coordinates.
+    #[allow(clippy::integer_arithmetic)]
+    const fn transform(&self, (x, y): (i64, i64), depth: i64, col: i64) -> (i64, i64) {
+        match self {
+            Self::North => (x + col, y + depth),
+            Self::South => (x + col, y - depth),
+            Self::East => (x + depth, y + col),
+            Self::West => (x - depth, y + col),
+        }
+    }
+}
+
+/// Exact fraction to avoid rounding errors when comparing tile columns with slopes.
+/// The denominator is always positive.
+#[derive(Clone, Copy)]
+struct Slope {
+    num: i64,
+    den: i64,
+}
+
+impl Slope {
+    const fn new(num: i64, den: i64) -> Self {
+        Self { num, den }
+    }
+
+    /// Slope of the left edge of the tile at `depth` and `col`.
+    #[allow(clippy::integer_arithmetic)]
+    const fn of(depth: i64, col: i64) -> Self {
+        Self::new(2 * col - 1, 2 * depth)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Row {
+    depth: i64,
+    start_slope: Slope,
+    end_slope: Slope,
+}
+
+#[allow(clippy::integer_arithmetic)]
+impl Row {
+    const fn first() -> Self {
+        Self {
+            depth: 1,
+            start_slope: Slope::new(-1, 1),
+            end_slope: Slope::new(1, 1),
+        }
+    }
+
+    const fn next(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..*self
+        }
+    }
+
+    /// `floor(depth * start_slope + 0.5)`
+    const fn min_col(&self) -> i64 {
+        let Slope { num, den } = self.start_slope;
+        (2 * self.depth * num + den).div_euclid(2 * den)
+    }
+
+    /// `ceil(depth * end_slope - 0.5)`
+    const fn max_col(&self) -> i64 {
+        let Slope { num, den } = self.end_slope;
+        -(den - 2 * self.depth * num).div_euclid(2 * den)
+    }
+
+    /// Floor tiles are only revealed if their center is inside the row's slopes.
+    const fn is_symmetric(&self, col: i64) -> bool {
+        col * self.start_slope.den >= self.depth * self.start_slope.num
+            && col * self.end_slope.den <= self.depth * self.end_slope.num
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visible_rows(visible: &BitGrid) -> Vec<String> {
+        (0..visible.rows())
+            .rev()
+            .map(|y| {
+                (0..visible.cols())
+                    .map(|x| if visible.get(x, y) { '*' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn pillars(tp: &TilePosition) -> bool {
+        [(2, 2), (5, 1), (3, 5), (6, 4), (1, 6), (7, 7), (4, 3)].contains(&(tp.x, tp.y))
+    }
+
+    #[test]
+    fn open_grid() {
+        let grid = Grid::new(5, 5, 1.0).unwrap();
+        let origin = TilePosition::new(2, 2, 0.5, 0.5);
+        let visible = ray_cast_fov(&grid, &origin, 10.0, |_| false);
+        assert_eq!(visible.count(), 25);
+    }
+
+    #[test]
+    fn radius() {
+        let grid = Grid::new(7, 7, 1.0).unwrap();
+        let origin = TilePosition::new(3, 3, 0.5, 0.5);
+        let visible = ray_cast_fov(&grid, &origin, 2.0, |_| false);
+        assert_eq!(
+            visible_rows(&visible),
+            [
+                ".......", //
+                "...*...", //
+                "..***..", //
+                ".*****.", //
+                "..***..", //
+                "...*...", //
+                ".......", //
+            ]
+        );
+    }
+
+    #[test]
+    fn walls_cast_shadows() {
+        let grid = Grid::new(7, 7, 1.0).unwrap();
+        let origin = TilePosition::new(1, 3, 0.5, 0.5);
+        let visible = ray_cast_fov(&grid, &origin, 10.0, |tp| tp.x == 3 && tp.y == 3);
+        assert_eq!(
+            visible_rows(&visible),
+            [
+                "*******", //
+                "*******", //
+                "******.", //
+                "****...", //
+                "******.", //
+                "*******", //
+                "*******", //
+            ]
+        );
+    }
+
+    #[test]
+    fn shadowcasting_radius() {
+        let grid = Grid::new(7, 7, 1.0).unwrap();
+        let origin = TilePosition::new(3, 3, 0.5, 0.5);
+        let visible = shadowcast_fov(&grid, &origin, 2.0, |_| false);
+        assert_eq!(
+            visible_rows(&visible),
+            [
+                ".......", //
+                "...*...", //
+                "..***..", //
+                ".*****.", //
+                "..***..", //
+                "...*...", //
+                ".......", //
+            ]
+        );
+    }
+
+    #[test]
+    fn rectangular_tiles_radius() {
+        // Tiles are twice as wide as high, so the radius covers twice as many rows as columns
+        let grid = Grid::new(7, 7, (2.0, 1.0)).unwrap();
+        let origin = TilePosition::new(3, 3, 1.0, 0.5);
+        let expected = [
+            ".......", //
+            "...*...", //
+            "...*...", //
+            "..***..", //
+            "...*...", //
+            "...*...", //
+            ".......", //
+        ];
+        let visible = shadowcast_fov(&grid, &origin, 2.0, |_| false);
+        assert_eq!(visible_rows(&visible), expected);
+        let visible = ray_cast_fov(&grid, &origin, 2.0, |_| false);
+        assert_eq!(visible_rows(&visible), expected);
+    }
+
+    #[test]
+    fn shadowcasting_walls_cast_shadows() {
+        let grid = Grid::new(7, 7, 1.0).unwrap();
+        let origin = TilePosition::new(1, 3, 0.5, 0.5);
+        let visible = shadowcast_fov(&grid, &origin, 10.0, |tp| tp.x == 3 && tp.y == 3);
+        assert_eq!(
+            visible_rows(&visible),
+            [
+                "*******", //
+                "*******", //
+                "******.", //
+                "****...", //
+                "******.", //
+                "*******", //
+                "*******", //
+            ]
+        );
+    }
+
+    #[test]
+    fn shadowcasting_is_symmetric() {
+        let grid = Grid::new(9, 9, 1.0).unwrap();
+        let floors: Vec<TilePosition> = (0..grid.rows)
+            .flat_map(|y| (0..grid.cols).map(move |x| TilePosition::new(x, y, 0.5, 0.5)))
+            .filter(|tp| !pillars(tp))
+            .collect();
+        let fovs: Vec<BitGrid> = floors
+            .iter()
+            .map(|tp| shadowcast_fov(&grid, tp, 20.0, pillars))
+            .collect();
+
+        for (a, fov_a) in floors.iter().zip(&fovs) {
+            for (b, fov_b) in floors.iter().zip(&fovs) {
+                assert_eq!(
+                    fov_a.get(b.x, b.y),
+                    fov_b.get(a.x, a.y),
+                    "{:?} <-> {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+}
@@ -1,23 +1,130 @@
+use crate::{position::WorldCoords, CrissCrossError, CrissCrossResult, TilePosition};
+
+/// Dimensions of a single tile in world units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileSize {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl TileSize {
+    #[must_use]
+    pub const fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+
+    /// Size of a tile whose width equals its height.
+    #[must_use]
+    pub const fn square(size: f32) -> Self {
+        Self::new(size, size)
+    }
+
+    /// The smaller of width and height.
+    pub fn min(&self) -> f32 {
+        self.width.min(self.height)
+    }
+
+    pub(crate) fn validated(self) -> CrissCrossResult<Self> {
+        let Self { width, height } = self;
+        if width.is_finite() && width > 0.0 && height.is_finite() && height > 0.0 {
+            Ok(self)
+        } else {
+            Err(CrissCrossError::InvalidTileSize { width, height })
+        }
+    }
+}
+
+impl From<f32> for TileSize {
+    fn from(size: f32) -> Self {
+        Self::square(size)
+    }
+}
+
+impl From<(f32, f32)> for TileSize {
+    fn from((width, height): (f32, f32)) -> Self {
+        Self::new(width, height)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Grid {
     pub cols: u32,
     pub rows: u32,
-    pub tile_size: f32,
+    pub tile_size: TileSize,
     pub width: f64,
     pub height: f64,
+    /// World position of the bottom left corner of the grid.
+    pub origin_x: f32,
+    pub origin_y: f32,
+    /// If `true` rays leaving the grid re-enter it at the opposite edge, see
+    /// [`Grid::with_wrapping`].
+    pub wrapping: bool,
 }
 
 impl Grid {
-    #[must_use]
-    pub fn new(cols: u32, rows: u32, tile_size: f32) -> Self {
-        let width = f64::from(cols) * f64::from(tile_size);
-        let height = f64::from(rows) * f64::from(tile_size);
-        Self {
+    /// Creates a grid of `cols` x `rows` tiles.
+    /// The `tile_size` is either a single `f32` for square tiles or a [`TileSize`] or
+    /// `(width, height)` tuple for rectangular ones.
+    ///
+    /// # Errors
+    ///
+    /// [`CrissCrossError::InvalidTileSize`] unless tile width and height are positive and
+    /// finite.
+    pub fn new<T: Into<TileSize>>(cols: u32, rows: u32, tile_size: T) -> CrissCrossResult<Self> {
+        let tile_size = tile_size.into().validated()?;
+        Ok(Self {
             cols,
             rows,
             tile_size,
-            width,
-            height,
-        }
+            width: f64::from(cols) * f64::from(tile_size.width),
+            height: f64::from(rows) * f64::from(tile_size.height),
+            origin_x: 0.0,
+            origin_y: 0.0,
+            wrapping: false,
+        })
+    }
+
+    /// Places the bottom left corner of the grid at the given world position.
+    #[must_use]
+    pub const fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin_x = x;
+        self.origin_y = y;
+        self
+    }
+
+    /// Makes the grid wrap around its edges, i.e. a ray leaving it through one edge re-enters it
+    /// through the opposite one and keeps yielding intersections with wrapped tile coordinates.
+    /// Since such rays never leave the grid they need to be limited to a maximum distance, see
+    /// [`crate::TileRaycaster::cast_ray_ranged`].
+    /// Field of view computations don't wrap.
+    #[must_use]
+    pub const fn with_wrapping(mut self) -> Self {
+        self.wrapping = true;
+        self
+    }
+
+    /// Returns `true` if the tile position lies inside the grid and its relative offset inside
+    /// its tile.
+    pub fn contains(&self, tp: &TilePosition) -> bool {
+        let TileSize { width, height } = self.tile_size;
+        tp.x < self.cols
+            && tp.y < self.rows
+            && (0.0..=width).contains(&tp.rel_x)
+            && (0.0..=height).contains(&tp.rel_y)
+    }
+
+    /// Converts absolute world coordinates into the position inside the tile containing them.
+    /// Returns `None` if they lie outside of the grid.
+    pub fn tile_position_at(&self, x: f32, y: f32) -> Option<TilePosition> {
+        WorldCoords::new(x - self.origin_x, y - self.origin_y, self.tile_size)
+            .bounds_checked(self)?
+            .to_tile_position()
+            .ok()
+    }
+
+    /// Converts a position inside a tile of this grid into absolute world coordinates.
+    pub fn world_position(&self, tp: &TilePosition) -> (f32, f32) {
+        let wc = WorldCoords::from_tile_position(tp, self.tile_size);
+        (wc.x + self.origin_x, wc.y + self.origin_y)
     }
 }
@@ -0,0 +1,357 @@
+use std::f32::consts::FRAC_PI_3;
+
+use crate::{
+    angle::{validated_direction, Direction},
+    ray_range::RayRange,
+    tile_raycaster::{crossing_of, Crossing},
+    util::{floats_equal, validated_distance, CORNER_TOLERANCE},
+    CrissCrossError, CrissCrossResult,
+};
+
+const SQRT_3: f32 = 1.732_050_8;
+
+// Axial offsets of the neighbors across the edges whose outward normals point at 0°, 60°, 120°,
+// 180°, 240° and 300° respectively.
+const NEIGHBORS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// Position inside a hex given by the axial coordinates of the hex and the offset from its
+/// center.
+#[derive(Debug, Clone)]
+pub struct HexPosition {
+    pub q: i32,
+    pub r: i32,
+    // Offset from the hex center
+    pub rel_x: f32,
+    pub rel_y: f32,
+}
+
+impl PartialEq for HexPosition {
+    fn eq(&self, other: &Self) -> bool {
+        self.q == other.q
+            && self.r == other.r
+            && floats_equal(self.rel_x, other.rel_x)
+            && floats_equal(self.rel_y, other.rel_y)
+    }
+}
+
+impl HexPosition {
+    #[must_use]
+    pub const fn new(q: i32, r: i32, rel_x: f32, rel_y: f32) -> Self {
+        Self { q, r, rel_x, rel_y }
+    }
+
+    /// Position at the center of the given hex.
+    #[must_use]
+    pub const fn center(q: i32, r: i32) -> Self {
+        Self::new(q, r, 0.0, 0.0)
+    }
+
+    pub const fn is_same_hex(&self, other: &Self) -> bool {
+        self.q == other.q && self.r == other.r
+    }
+}
+
+/// Hexagon shaped grid of pointy-top hexes which includes all hexes within `radius` steps of
+/// the hex at `(0, 0)`.
+///
+/// The center of that hex is located at world coordinates `(0.0, 0.0)`, the `q` axis points
+/// right and the `r` axis up and to the right at 60°.
+#[derive(Debug, Clone)]
+pub struct HexGrid {
+    /// Distance from the center of a hex to each of its corners.
+    pub hex_size: f32,
+    pub radius: u32,
+}
+
+impl HexGrid {
+    /// # Errors
+    ///
+    /// [`CrissCrossError::InvalidTileSize`] unless the hex size is positive and finite.
+    pub fn new(hex_size: f32, radius: u32) -> CrissCrossResult<Self> {
+        if hex_size.is_finite() && hex_size > 0.0 {
+            Ok(Self { hex_size, radius })
+        } else {
+            Err(CrissCrossError::InvalidTileSize {
+                width: hex_size,
+                height: hex_size,
+            })
+        }
+    }
+
+    /// Returns `true` if the hex is part of the grid.
+    #[allow(clippy::integer_arithmetic)]
+    pub fn contains_hex(&self, q: i32, r: i32) -> bool {
+        // Twice the number of steps from the center hex
+        let (q, r) = (i64::from(q), i64::from(r));
+        q.abs() + r.abs() + (q + r).abs() <= 2 * i64::from(self.radius)
+    }
+
+    /// Returns `true` if the hex is part of the grid and the offset lies inside of it.
+    /// Offsets on the edges of the hex are considered to be inside.
+    pub fn contains(&self, hp: &HexPosition) -> bool {
+        // Distance from the center to the edges, the offset needs to lie on the inner side of
+        // each pair of opposite edges
+        let apothem = self.hex_size * (SQRT_3 / 2.0 + CORNER_TOLERANCE);
+        let (x, y) = (hp.rel_x, hp.rel_y);
+        self.contains_hex(hp.q, hp.r)
+            && x.abs() <= apothem
+            && (SQRT_3 / 2.0).mul_add(y, 0.5 * x).abs() <= apothem
+            && (SQRT_3 / 2.0).mul_add(y, -0.5 * x).abs() <= apothem
+    }
+
+    /// World coordinates of the center of the given hex.
+    #[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+    pub fn center(&self, q: i32, r: i32) -> (f32, f32) {
+        let (q, r) = (q as f32, r as f32);
+        (
+            self.hex_size * SQRT_3 * r.mul_add(0.5, q),
+            self.hex_size * 1.5 * r,
+        )
+    }
+
+    /// Converts a position inside a hex into world coordinates.
+    pub fn world_position(&self, hp: &HexPosition) -> (f32, f32) {
+        let (x, y) = self.center(hp.q, hp.r);
+        (x + hp.rel_x, y + hp.rel_y)
+    }
+
+    /// Converts world coordinates into the position inside the hex containing them.
+    /// Returns `None` if they lie outside of the grid.
+    pub fn hex_position_at(&self, x: f32, y: f32) -> Option<HexPosition> {
+        let (q, r) = self.hex_at(x, y);
+        if !self.contains_hex(q, r) {
+            return None;
+        }
+        let (cx, cy) = self.center(q, r);
+        Some(HexPosition::new(q, r, x - cx, y - cy))
+    }
+
+    /// Axial coordinates of the hex containing the world coordinates, obtained by rounding
+    /// their cube coordinates.
+    #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+    fn hex_at(&self, world_x: f32, world_y: f32) -> (i32, i32) {
+        let frac_q = (SQRT_3 / 3.0).mul_add(world_x, -world_y / 3.0) / self.hex_size;
+        let frac_r = (2.0 / 3.0) * world_y / self.hex_size;
+        let frac_s = -frac_q - frac_r;
+        let (q, r, s) = (frac_q.round(), frac_r.round(), frac_s.round());
+        let (dq, dr, ds) = ((q - frac_q).abs(), (r - frac_r).abs(), (s - frac_s).abs());
+        // Fix up the coordinate that was rounded the most so that q + r + s == 0
+        let (q, r) = if dq > dr && dq > ds {
+            (-r - s, r)
+        } else if dr > ds {
+            (q, -q - s)
+        } else {
+            (q, r)
+        };
+        (q as i32, r as i32)
+    }
+}
+
+/// Casts rays across a [`HexGrid`] offering the same predicate driven helpers as
+/// [`crate::TileRaycaster`].
+pub struct HexRaycaster {
+    grid: HexGrid,
+}
+
+/// Rays are cast along a [`Direction`], i.e. either an angle in radians or a direction vector
+/// like `(dx, dy)`.
+/// All methods taking a [`HexPosition`] or direction return an error if the position lies
+/// outside of the grid or the direction isn't a finite, non-zero vector respectively.
+impl HexRaycaster {
+    #[must_use]
+    pub const fn new(grid: HexGrid) -> Self {
+        Self { grid }
+    }
+
+    pub const fn grid(&self) -> &HexGrid {
+        &self.grid
+    }
+
+    /// Yields the positions at which the ray enters each hex it crosses until it leaves the
+    /// grid.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::HexOutOfBounds`] if `hp` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    pub fn cast_ray<T: Into<Direction>>(
+        &self,
+        hp: &HexPosition,
+        direction: T,
+    ) -> CrissCrossResult<HexRayIter> {
+        if !self.grid.contains(hp) {
+            return Err(CrissCrossError::HexOutOfBounds { q: hp.q, r: hp.r });
+        }
+        let direction = validated_direction(direction)?;
+        Ok(HexRayIter::new(self.grid.clone(), hp, &direction))
+    }
+
+    /// Like [`HexRaycaster::cast_ray`], but stops once the ray traveled `max_distance` in world
+    /// units.
+    /// The point at exactly that distance is emitted as the last intersection, unless the ray
+    /// leaves the grid before reaching it.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::HexOutOfBounds`] if `hp` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::InvalidDistance`] if `max_distance` is negative or NaN.
+    pub fn cast_ray_ranged<T: Into<Direction>>(
+        &self,
+        hp: &HexPosition,
+        direction: T,
+        max_distance: f32,
+    ) -> CrissCrossResult<HexRayIter> {
+        let mut iter = self.cast_ray(hp, direction)?;
+        iter.range = RayRange::new(validated_distance(max_distance)?);
+        Ok(iter)
+    }
+
+    /// Returns the last hex along the ray before the first one for which `is_valid` returns
+    /// `false`.
+    ///
+    /// # Errors
+    ///
+    /// See [`HexRaycaster::cast_ray`].
+    pub fn last_valid<P, T: Into<Direction>>(
+        &self,
+        hp: &HexPosition,
+        direction: T,
+        is_valid: P,
+    ) -> CrissCrossResult<Option<HexPosition>>
+    where
+        P: FnMut(&HexPosition) -> bool,
+    {
+        Ok(self.cast_ray(hp, direction)?.take_while(is_valid).last())
+    }
+
+    /// Returns the first hex along the ray for which `is_valid` returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// See [`HexRaycaster::cast_ray`].
+    pub fn first_invalid<P, T: Into<Direction>>(
+        &self,
+        hp: &HexPosition,
+        direction: T,
+        is_valid: P,
+    ) -> CrissCrossResult<Option<HexPosition>>
+    where
+        P: FnMut(&HexPosition) -> bool,
+    {
+        let mut iter = self.cast_ray(hp, direction)?.skip_while(is_valid);
+        Ok(iter.next())
+    }
+
+    /// Last valid and first invalid position along the ray, see [`Crossing`].
+    ///
+    /// # Errors
+    ///
+    /// See [`HexRaycaster::cast_ray`].
+    pub fn crossing<P, T: Into<Direction>>(
+        &self,
+        hp: &HexPosition,
+        direction: T,
+        is_valid: P,
+    ) -> CrissCrossResult<Crossing<HexPosition>>
+    where
+        P: FnMut(&HexPosition) -> bool,
+    {
+        Ok(crossing_of(self.cast_ray(hp, direction)?, is_valid))
+    }
+}
+
+/// Iterates over the positions at which a ray enters each hex it crosses.
+#[derive(Debug)]
+pub struct HexRayIter {
+    grid: HexGrid,
+    origin: (f32, f32),
+    cos: f32,
+    sin: f32,
+    hex: (i32, i32),
+    range: RayRange,
+}
+
+impl HexRayIter {
+    fn new(grid: HexGrid, hp: &HexPosition, direction: &Direction) -> Self {
+        let origin = grid.world_position(hp);
+        Self {
+            grid,
+            origin,
+            cos: direction.x(),
+            sin: direction.y(),
+            hex: (hp.q, hp.r),
+            range: RayRange::default(),
+        }
+    }
+
+    fn point_at(&self, distance: f32) -> (f32, f32) {
+        let (x, y) = self.origin;
+        (self.cos.mul_add(distance, x), self.sin.mul_add(distance, y))
+    }
+
+    fn position_at(&self, (q, r): (i32, i32), distance: f32) -> HexPosition {
+        let (x, y) = self.point_at(distance);
+        let (cx, cy) = self.grid.center(q, r);
+        HexPosition::new(q, r, x - cx, y - cy)
+    }
+
+    /// Distance at which the ray leaves the current hex together with the hex it enters.
+    #[allow(clippy::integer_arithmetic)]
+    fn exit(&self) -> (f32, (i32, i32)) {
+        let (q, r) = self.hex;
+        let (cx, cy) = self.grid.center(q, r);
+        let (ox, oy) = (self.origin.0 - cx, self.origin.1 - cy);
+        let inradius = self.grid.hex_size * SQRT_3 / 2.0;
+
+        let mut nearest = (f32::INFINITY, (q, r));
+        let mut second = f32::INFINITY;
+        for (idx, (dq, dr)) in (0_u8..).zip(NEIGHBORS.iter()) {
+            let normal = f32::from(idx) * FRAC_PI_3;
+            let (nx, ny) = (normal.cos(), normal.sin());
+            let towards = self.cos.mul_add(nx, self.sin * ny);
+            if towards <= f32::EPSILON {
+                continue;
+            }
+            let distance = (inradius - ox.mul_add(nx, oy * ny)) / towards;
+            if distance < nearest.0 {
+                second = nearest.0;
+                nearest = (distance, (q + dq, r + dr));
+            } else {
+                second = second.min(distance);
+            }
+        }
+
+        let (distance, next) = nearest;
+        if second - distance < CORNER_TOLERANCE * self.grid.hex_size {
+            // Passing through a corner, enter whichever hex lies beyond it
+            let beyond = (CORNER_TOLERANCE * self.grid.hex_size).mul_add(10.0, distance);
+            let (x, y) = self.point_at(beyond);
+            (distance, self.grid.hex_at(x, y))
+        } else {
+            (distance, next)
+        }
+    }
+}
+
+impl Iterator for HexRayIter {
+    type Item = HexPosition;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_ended() {
+            return None;
+        }
+        let (distance, next) = self.exit();
+        if !self.range.within(distance) {
+            let max_distance = self.range.finish()?;
+            return Some(self.position_at(self.hex, max_distance));
+        }
+        if !self.grid.contains_hex(next.0, next.1) {
+            self.range.stop();
+            return None;
+        }
+        self.hex = next;
+        self.range.crossed(distance);
+        Some(self.position_at(next, distance))
+    }
+}
@@ -63,16 +63,38 @@ pub use test_utils::*;
 mod angle;
 mod beam;
 mod beam_iter;
+mod columns;
+mod error;
+mod fov;
 mod grid;
+mod hex;
 mod position;
 mod ray;
+mod ray_hit;
 mod ray_iter;
+mod ray_range;
 mod rays;
+mod swept_beam;
+mod tile_map;
 mod tile_raycaster;
+mod unbounded;
 mod util;
+mod voxel;
 
-pub use angle::AngleRad;
-pub use beam::BeamIntersect;
-pub use grid::Grid;
-pub use position::TilePosition;
-pub use tile_raycaster::{Crossing, TileRaycaster};
+pub use angle::{AngleRad, Direction};
+pub use beam::{BeamHit, BeamIntersect};
+pub use beam_iter::{BeamHitIter, BeamIter, OccludedBeamIter};
+pub use columns::{Camera, Column, ColumnHit};
+pub use error::{CrissCrossError, CrissCrossResult};
+pub use fov::FovAlgorithm;
+pub use grid::{Grid, TileSize};
+pub use hex::{HexGrid, HexPosition, HexRayIter, HexRaycaster};
+pub use position::{SignedTilePosition, TilePosition, WorldCoords};
+pub use ray_hit::{RayHit, TileEdge};
+pub use ray_iter::{RayHitIter, RayIter};
+pub use rays::{BeamLayout, BeamRay};
+pub use swept_beam::{SweptBeamIter, SweptTile};
+pub use tile_map::{BitGrid, TileMap};
+pub use tile_raycaster::{BeamCrossing, Crossing, TileRaycaster};
+pub use unbounded::{SignedRayIter, UnboundedRaycaster};
+pub use voxel::{VoxelGrid, VoxelPosition, VoxelRayIter, VoxelRaycaster};
@@ -1,6 +1,9 @@
 use std::{convert::TryFrom, fmt, ops};
 
-use crate::util::{floats_equal, round, round_stp, round_tp};
+use crate::{
+    util::{floats_equal, round, round_stp, round_tp},
+    CrissCrossError, TileSize,
+};
 
 use super::WorldCoords;
 
@@ -52,10 +55,12 @@ impl TilePosition {
         }
     }
 
-    pub fn distance_global<'a, T>(&self, other: T, tile_size: f32) -> f32
+    pub fn distance_global<'a, T, S>(&self, other: T, tile_size: S) -> f32
     where
         T: Into<&'a Self>,
+        S: Into<TileSize>,
     {
+        let tile_size = tile_size.into();
         self.to_world_coords(tile_size)
             .distance(&other.into().to_world_coords(tile_size))
     }
@@ -87,12 +92,13 @@ impl TilePosition {
         (x2 - x1, y2 - y1)
     }
 
-    fn to_world_coords(&self, tile_size: f32) -> WorldCoords {
+    fn to_world_coords(&self, tile_size: TileSize) -> WorldCoords {
         WorldCoords::from_tile_position(self, tile_size)
     }
 }
 
 impl SignedTilePosition {
+    #[must_use]
     pub fn new(x: i64, y: i64, rel_x: f32, rel_y: f32) -> Self {
         Self {
             x,
@@ -102,15 +108,14 @@ impl SignedTilePosition {
         }
     }
 
-    pub fn normalized(self, tile_size: f32) -> Self {
-        let dts = 2.0 * tile_size;
-        let Self { x, y, rel_x, rel_y } = self;
-        debug_assert!(-dts < rel_x && rel_x < dts);
-        debug_assert!(-dts < rel_y && rel_y < dts);
+    #[must_use]
+    pub fn normalized<S: Into<TileSize>>(self, tile_size: S) -> Self {
+        let tile_size = tile_size.into();
+        let (dtw, dth) = (2.0 * tile_size.width, 2.0 * tile_size.height);
+        debug_assert!(-dtw < self.rel_x && self.rel_x < dtw);
+        debug_assert!(-dth < self.rel_y && self.rel_y < dth);
 
-        #[allow(clippy::clippy::cast_precision_loss)]
-        // pub const MAX: f32 = 3.40282347e+38_f32
-        WorldCoords::new(x as f32 + rel_x, y as f32 + rel_y, tile_size).to_signed_tile_position()
+        WorldCoords::from_signed_tile_position(&self, tile_size).to_signed_tile_position()
     }
 }
 
@@ -126,7 +131,7 @@ impl From<TilePosition> for SignedTilePosition {
 }
 
 impl TryFrom<SignedTilePosition> for TilePosition {
-    type Error = String;
+    type Error = CrissCrossError;
     #[allow(
         clippy::clippy::cast_sign_loss,
         clippy::cast_precision_loss,
@@ -145,7 +150,7 @@ impl TryFrom<SignedTilePosition> for TilePosition {
                 rel_y: stp.rel_y,
             })
         } else {
-            Err("Tile Position is off grid, cannot convert".to_string())
+            Err(CrissCrossError::NegativeTileCoordinates { x: stp.x, y: stp.y })
         }
     }
 }
@@ -230,7 +235,6 @@ impl ops::Add<SignedTilePosition> for TilePosition {
     }
 }
 
-#[cfg(test)]
 impl From<((i64, f32), (i64, f32))> for SignedTilePosition {
     fn from(((x, rel_x), (y, rel_y)): ((i64, f32), (i64, f32))) -> Self {
         Self::new(x, y, rel_x, rel_y)
@@ -2,22 +2,27 @@ use std::{convert::TryInto, fmt};
 
 use crate::{
     util::{round, round_wc},
-    Grid,
+    CrissCrossError, Grid, TileSize,
 };
 
 use super::{SignedTilePosition, TilePosition};
 
 const WORLD_POSITION_PRECISION: usize = 8;
 
+/// Position in world units relative to the bottom left corner of a grid, i.e. not taking its
+/// origin into account, see [`Grid::with_origin`].
 #[derive(Clone, PartialEq)]
 pub struct WorldCoords {
     pub(crate) x: f32,
     pub(crate) y: f32,
-    pub(crate) tile_size: f32,
+    pub(crate) tile_size: TileSize,
 }
 
 impl WorldCoords {
-    pub(crate) fn new(x: f32, y: f32, tile_size: f32) -> Self {
+    /// Creates world coordinates on a grid whose tiles have the given size.
+    #[must_use]
+    pub fn new<T: Into<TileSize>>(x: f32, y: f32, tile_size: T) -> Self {
+        let tile_size = tile_size.into();
         Self {
             x: round(x, WORLD_POSITION_PRECISION),
             y: round(y, WORLD_POSITION_PRECISION),
@@ -25,6 +30,19 @@ impl WorldCoords {
         }
     }
 
+    pub const fn x(&self) -> f32 {
+        self.x
+    }
+
+    pub const fn y(&self) -> f32 {
+        self.y
+    }
+
+    pub const fn tile_size(&self) -> TileSize {
+        self.tile_size
+    }
+
+    #[must_use]
     pub fn translated(&self, dx: f32, dy: f32) -> Self {
         Self {
             x: self.x + dx,
@@ -33,37 +51,58 @@ impl WorldCoords {
         }
     }
 
-    pub(crate) fn distance(&self, other: &Self) -> f32 {
+    pub fn distance(&self, other: &Self) -> f32 {
         let dx = self.x - other.x;
         let dy = self.y - other.y;
         dx.hypot(dy)
     }
 
+    #[must_use]
     #[allow(clippy::cast_precision_loss)]
-    pub(crate) fn from_tile_position(tp: &TilePosition, tile_size: f32) -> Self {
-        let x = tile_size.mul_add(tp.x as f32, tp.rel_x);
-        let y = tile_size.mul_add(tp.y as f32, tp.rel_y);
+    pub fn from_tile_position<T: Into<TileSize>>(tp: &TilePosition, tile_size: T) -> Self {
+        let tile_size = tile_size.into();
+        let x = tile_size.width.mul_add(tp.x as f32, tp.rel_x);
+        let y = tile_size.height.mul_add(tp.y as f32, tp.rel_y);
         Self::new(x, y, tile_size)
     }
 
     #[allow(clippy::cast_precision_loss)]
-    pub(crate) fn from_signed_tile_position(tp: &SignedTilePosition, tile_size: f32) -> Self {
-        let x = tile_size.mul_add(tp.x as f32, tp.rel_x);
-        let y = tile_size.mul_add(tp.y as f32, tp.rel_y);
+    pub(crate) fn from_signed_tile_position(tp: &SignedTilePosition, tile_size: TileSize) -> Self {
+        let x = tile_size.width.mul_add(tp.x as f32, tp.rel_x);
+        let y = tile_size.height.mul_add(tp.y as f32, tp.rel_y);
         Self::new(x, y, tile_size)
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn to_tile_position(&self) -> Result<TilePosition, String> {
+    /// Converts into the position inside the tile containing these coordinates.
+    ///
+    /// # Errors
+    ///
+    /// [`CrissCrossError::NegativeTileCoordinates`] if they lie left of or below the grid.
+    pub fn to_tile_position(&self) -> Result<TilePosition, CrissCrossError> {
         self.to_signed_tile_position().try_into()
     }
 
+    /// Like [`WorldCoords::to_tile_position`], but checks the bounds of the given grid.
+    ///
+    /// # Errors
+    ///
+    /// [`CrissCrossError::OutOfBounds`] if the coordinates lie outside of the grid.
+    pub fn to_tile_position_in(&self, grid: &Grid) -> Result<TilePosition, CrissCrossError> {
+        self.bounds_checked(grid)
+            .ok_or(CrissCrossError::OutOfBounds {
+                x: self.x,
+                y: self.y,
+            })?
+            .to_tile_position()
+    }
+
     #[allow(clippy::cast_possible_truncation)]
     pub(crate) fn to_signed_tile_position(&self) -> SignedTilePosition {
-        let x = (self.x / self.tile_size).trunc() as i64;
-        let y = (self.y / self.tile_size).trunc() as i64;
-        let rel_x = self.x % self.tile_size;
-        let rel_y = self.y % self.tile_size;
+        let TileSize { width, height } = self.tile_size;
+        let x = (self.x / width).trunc() as i64;
+        let y = (self.y / height).trunc() as i64;
+        let rel_x = self.x % width;
+        let rel_y = self.y % height;
         SignedTilePosition::new(x, y, rel_x, rel_y)
     }
 
@@ -86,14 +125,14 @@ impl fmt::Debug for WorldCoords {
             let wc = round_wc(self);
             write!(
                 f,
-                "({:.*}, {:.*}), {:.*}",
-                p, wc.x, p, wc.y, p, wc.tile_size,
+                "({:.*}, {:.*}), ({:.*}, {:.*})",
+                p, wc.x, p, wc.y, p, wc.tile_size.width, p, wc.tile_size.height,
             )
         } else {
             write!(
                 f,
-                "WorldCoords {{ x: {:.*}, y: {:.*}, tile_size: {:.*} }}).into()",
-                p, self.x, p, self.y, p, self.tile_size,
+                "WorldCoords {{ x: {:.*}, y: {:.*}, tile_size: {:?} }}).into()",
+                p, self.x, p, self.y, self.tile_size,
             )
         }
     }
@@ -1,11 +1,12 @@
 use std::convert::TryInto;
 
 use crate::{
-    angle::{DirectionX, DirectionY},
-    grid::Grid,
+    angle::{Direction, DirectionX, DirectionY},
+    grid::{Grid, TileSize},
     position::{SignedTilePosition, TilePosition, WorldCoords},
-    util::floats_equal,
-    AngleRad,
+    ray_hit::{RayHit, TileEdge},
+    ray_range::RayRange,
+    util::{floats_equal, CORNER_TOLERANCE},
 };
 
 fn normalize_zeros(tp: &mut SignedTilePosition) {
@@ -18,19 +19,83 @@ fn normalize_zeros(tp: &mut SignedTilePosition) {
     }
 }
 
+/// Moves positions located on the left or bottom edge of a tile into the previous tile when
+/// moving left or down respectively, since that is the tile the ray is entering.
+#[allow(clippy::integer_arithmetic)]
+fn normalize(
+    direction_x: &DirectionX,
+    direction_y: &DirectionY,
+    grid: &Grid,
+    tp: &mut SignedTilePosition,
+) {
+    if *direction_x == DirectionX::Left && floats_equal(tp.rel_x, 0.0) {
+        tp.x -= 1;
+        tp.rel_x += grid.tile_size.width;
+    }
+    if *direction_y == DirectionY::Down && floats_equal(tp.rel_y, 0.0) {
+        tp.y -= 1;
+        tp.rel_y += grid.tile_size.height;
+    }
+    normalize_zeros(tp);
+}
+
+// Rays entering the grid closer than this to one of its corners are considered to pass
+// through that corner.
+const ENTRY_TOLERANCE: f32 = 1e-5;
+
+/// Range of distances along a ray at which its coordinate starting at `start` and changing by
+/// `delta` per unit travelled lies within `0.0..=size`.
+fn slab(start: f32, delta: f32, size: f32, parallel: bool) -> Option<(f32, f32)> {
+    if parallel {
+        if (0.0..=size).contains(&start) {
+            Some((f32::NEG_INFINITY, f32::INFINITY))
+        } else {
+            None
+        }
+    } else {
+        let (t1, t2) = (-start / delta, (size - start) / delta);
+        Some((t1.min(t2), t1.max(t2)))
+    }
+}
+
+/// Coordinates on a wrapping grid wrapped around so that they lie inside of it.
+#[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
+fn wrapped(grid: &Grid, wc: &WorldCoords) -> WorldCoords {
+    if grid.wrapping {
+        let (width, height) = (grid.width as f32, grid.height as f32);
+        WorldCoords::new(
+            wc.x.rem_euclid(width),
+            wc.y.rem_euclid(height),
+            wc.tile_size,
+        )
+    } else {
+        wc.clone()
+    }
+}
+
 /// Assumes origin (0, 0) is at bottom left.
 /// Assumes relative tile position are based on (0.0, 0.0) being located at the bottom left of each
 /// tile.
 #[derive(Debug)]
 pub struct Ray {
-    tan: f32,
+    direction: Direction,
     direction_x: DirectionX,
     direction_y: DirectionY,
     grid: Grid,
-    intersect_x: Option<TilePosition>,
-    intersect_y: Option<TilePosition>,
-    delta_x_axis_intersect: Option<SignedTilePosition>,
-    delta_y_axis_intersect: Option<SignedTilePosition>,
+    // Tile the ray is currently located in, not wrapped around on wrapping grids
+    tile: (i64, i64),
+    // Distances from the origin to the next vertical/horizontal edge the ray crosses
+    distance_x: f32,
+    distance_y: f32,
+    // Column/row whose left/bottom edge the ray crosses next
+    next_col: i64,
+    next_row: i64,
+    range: RayRange,
+    length: f32,
+    end: Option<TilePosition>,
+    // Distance from the point the ray was cast from to `wc`, which is where it enters the grid
+    offset: f32,
+    entry: Option<RayHit>,
     pub(crate) wc: WorldCoords,
     pub(crate) tp: TilePosition,
 }
@@ -39,104 +104,277 @@ pub struct Ray {
 // Constructor API
 //
 impl Ray {
-    pub(crate) fn new<T>(grid: Grid, tp: TilePosition, angle: T) -> Self
+    #[allow(clippy::integer_arithmetic)]
+    pub(crate) fn new<T>(grid: Grid, tp: TilePosition, direction: T) -> Self
     where
-        T: Into<AngleRad>,
+        T: Into<Direction>,
     {
         let wc = WorldCoords::from_tile_position(&tp, grid.tile_size);
-        let angle = (angle).into().clamp();
-        let tan = angle.0.tan();
-
-        let direction_x: DirectionX = (&angle).into();
-        let direction_y: DirectionY = (&angle).into();
-
-        let delta_x_axis_intersects = {
-            (match direction_x {
-                DirectionX::Left => Some(-grid.tile_size),
-                DirectionX::Right => Some(grid.tile_size),
-                DirectionX::Parallel => None,
-            })
-            .map(|dx| {
-                let dy = tan * dx;
-                let mut stp = WorldCoords::new(dx, dy, grid.tile_size).to_signed_tile_position();
-                normalize_zeros(&mut stp);
-                stp
-            })
+        let direction = direction.into();
+        let direction_x: DirectionX = (&direction).into();
+        let direction_y: DirectionY = (&direction).into();
+        let next_col = match direction_x {
+            DirectionX::Right => i64::from(tp.x) + 1,
+            DirectionX::Left | DirectionX::Parallel => i64::from(tp.x),
         };
-
-        let delta_y_axis_intersects = {
-            (match direction_y {
-                DirectionY::Up => Some(grid.tile_size),
-                DirectionY::Down => Some(-grid.tile_size),
-                DirectionY::Parallel => None,
-            })
-            .map(|dy| {
-                let dx = dy / tan;
-                let mut stp = WorldCoords::new(dx, dy, grid.tile_size).to_signed_tile_position();
-                normalize_zeros(&mut stp);
-                stp
-            })
+        let next_row = match direction_y {
+            DirectionY::Up => i64::from(tp.y) + 1,
+            DirectionY::Down | DirectionY::Parallel => i64::from(tp.y),
         };
 
         let mut me = Self {
             grid,
-            tp,
             wc,
-            tan,
+            direction,
             direction_x,
             direction_y,
-            intersect_x: None,
-            intersect_y: None,
-            delta_x_axis_intersect: delta_x_axis_intersects,
-            delta_y_axis_intersect: delta_y_axis_intersects,
+            tile: (i64::from(tp.x), i64::from(tp.y)),
+            distance_x: f32::INFINITY,
+            distance_y: f32::INFINITY,
+            next_col,
+            next_row,
+            range: RayRange::default(),
+            length: 0.0,
+            end: None,
+            offset: 0.0,
+            entry: None,
+            tp,
         };
-        me.intersect_x = me.initial_x_intersect();
-        me.intersect_y = me.initial_y_intersect();
+        me.length = me.distance_to_grid_edge();
+        me.distance_x = me.col_distance(next_col);
+        me.distance_y = me.row_distance(next_row);
 
         me
     }
-}
 
-//
-// Inital Intersects
-//
-impl Ray {
-    #[allow(clippy::integer_arithmetic)]
-    fn initial_x_intersect(&self) -> Option<TilePosition> {
-        if self.direction_x == DirectionX::Left && self.tp.x == 0
-            || self.direction_x == DirectionX::Right && self.tp.x + 1 == self.grid.cols
+    /// Creates a ray from a point that may lie outside of the grid.
+    /// If it does, the ray starts where it enters the grid and emits that point as its first
+    /// intersection. Returns `None` if the ray misses the grid entirely.
+    pub(crate) fn from_world<T>(grid: Grid, wc: &WorldCoords, direction: T) -> Option<Self>
+    where
+        T: Into<Direction>,
+    {
+        let direction = direction.into();
+        if grid.wrapping {
+            let tp = wrapped(&grid, wc).to_tile_position().ok()?;
+            return Some(Self::new(grid, tp, direction));
+        }
+        if let Some(tp) = wc
+            .bounds_checked(&grid)
+            .and_then(|wc| wc.to_tile_position().ok())
         {
+            return Some(Self::new(grid, tp, direction));
+        }
+
+        let direction_x = DirectionX::from(&direction);
+        let direction_y = DirectionY::from(&direction);
+        #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
+        let (width, height) = (grid.width as f32, grid.height as f32);
+        let (enter_x, exit_x) = slab(
+            wc.x,
+            direction.x(),
+            width,
+            direction_x == DirectionX::Parallel,
+        )?;
+        let (enter_y, exit_y) = slab(
+            wc.y,
+            direction.y(),
+            height,
+            direction_y == DirectionY::Parallel,
+        )?;
+        let enter = enter_x.max(enter_y);
+        if enter < 0.0 || exit_x.min(exit_y) - enter < ENTRY_TOLERANCE {
             return None;
         }
+        let at_corner = (enter_x - enter_y).abs() < ENTRY_TOLERANCE;
+        let via_x = at_corner || enter_x > enter_y;
+        let via_y = at_corner || enter_y > enter_x;
+
+        // Snap the entry point onto the grid boundary it crosses to avoid rounding errors
+        // placing it just outside of the grid
+        let entry = wc.translated(direction.x() * enter, direction.y() * enter);
+        let x = match direction_x {
+            _ if !via_x => entry.x.max(0.0).min(width),
+            DirectionX::Left => width,
+            DirectionX::Right | DirectionX::Parallel => 0.0,
+        };
+        let y = match direction_y {
+            _ if !via_y => entry.y.max(0.0).min(height),
+            DirectionY::Down => height,
+            DirectionY::Up | DirectionY::Parallel => 0.0,
+        };
+        let mut stp = WorldCoords::new(x, y, grid.tile_size).to_signed_tile_position();
+        normalize(&direction_x, &direction_y, &grid, &mut stp);
+        let tp: TilePosition = stp.try_into().ok()?;
+
+        let mut ray = Self::new(grid, tp.clone(), direction);
+        ray.offset = enter;
+        ray.length += enter;
+        let edge = match (via_x, via_y) {
+            (true, true) => ray.corner_edge(),
+            (true, false) => ray.vertical_edge(),
+            (false, _) => ray.horizontal_edge(),
+        };
+        ray.entry = Some(ray.hit(tp, Some(edge), 0.0));
+        Some(ray)
+    }
+
+    /// Creates a ray from `from` towards `to` which emits `to` as its last intersection.
+    pub(crate) fn segment(grid: Grid, from: TilePosition, to: TilePosition) -> Self {
+        let start = WorldCoords::from_tile_position(&from, grid.tile_size);
+        let end = WorldCoords::from_tile_position(&to, grid.tile_size);
+        let direction = Direction::new(end.x - start.x, end.y - start.y);
+        Self::new(grid, from, direction).ending_at(start.distance(&end), to)
+    }
+
+    /// Like [`Ray::segment`] for a start point that may lie outside of the grid, see
+    /// [`Ray::from_world`].
+    pub(crate) fn segment_from_world(
+        grid: Grid,
+        start: &WorldCoords,
+        to: TilePosition,
+    ) -> Option<Self> {
+        let end = WorldCoords::from_tile_position(&to, grid.tile_size);
+        let direction = Direction::new(end.x - start.x, end.y - start.y);
+        Some(Self::from_world(grid, start, direction)?.ending_at(start.distance(&end), to))
+    }
 
-        let dx = match self.direction_x {
-            DirectionX::Left => Some(-self.tp.rel_x),
-            DirectionX::Right => Some(self.grid.tile_size - self.tp.rel_x),
-            DirectionX::Parallel => None,
-        }?;
+    const fn ending_at(mut self, length: f32, end: TilePosition) -> Self {
+        self.range = RayRange::exact(length);
+        self.length = length;
+        self.end = Some(end);
+        self
+    }
 
-        let dy = dx * self.tan;
+    /// Limits the ray to the given distance in world units.
+    /// The point at exactly that distance is emitted as the last intersection unless the ray
+    /// leaves the grid before reaching it.
+    pub(crate) fn ranged(mut self, max_distance: f32) -> Self {
+        let inside = max_distance - self.offset;
+        let dx = self.direction.x() * inside;
+        let dy = self.direction.y() * inside;
         let wc = self.wc.translated(dx, dy);
-        self.normalized_valid_tile_position(&wc)
+        let on_grid = self.grid.wrapping || wc.bounds_checked(&self.grid).is_some();
+        self.end = if on_grid && inside >= 0.0 {
+            self.normalized_valid_tile_position(&wc)
+        } else {
+            None
+        };
+        self.range = RayRange::new(max_distance);
+        self.length = max_distance;
+        self
     }
+}
 
-    #[allow(clippy::integer_arithmetic)]
-    fn initial_y_intersect(&self) -> Option<TilePosition> {
-        if self.direction_y == DirectionY::Down && self.tp.y == 0
-            || self.direction_y == DirectionY::Up && self.tp.y + 1 == self.grid.rows
-        {
-            return None;
+//
+// Distances
+//
+impl Ray {
+    /// Distance from the origin to the point where the ray leaves the grid.
+    fn distance_to_grid_edge(&self) -> f32 {
+        if self.grid.wrapping {
+            return f32::INFINITY;
         }
+        #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
+        let (width, height) = (self.grid.width as f32, self.grid.height as f32);
+        let along_x = match self.direction_x {
+            DirectionX::Left => -self.wc.x / self.direction.x(),
+            DirectionX::Right => (width - self.wc.x) / self.direction.x(),
+            DirectionX::Parallel => f32::INFINITY,
+        };
+        let along_y = match self.direction_y {
+            DirectionY::Up => (height - self.wc.y) / self.direction.y(),
+            DirectionY::Down => -self.wc.y / self.direction.y(),
+            DirectionY::Parallel => f32::INFINITY,
+        };
+        along_x.min(along_y)
+    }
 
-        let dy = match self.direction_y {
-            DirectionY::Up => Some(self.grid.tile_size - self.tp.rel_y),
-            DirectionY::Down => Some(-self.tp.rel_y),
-            DirectionY::Parallel => None,
-        }?;
+    /// `distance` is measured from the point at which the ray entered the grid.
+    fn hit(&self, tp: TilePosition, edge: Option<TileEdge>, distance: f32) -> RayHit {
+        let distance = self.offset + distance;
+        let t = if self.length > 0.0 {
+            (distance / self.length).min(1.0)
+        } else {
+            1.0
+        };
+        RayHit {
+            tp,
+            edge,
+            distance,
+            t,
+        }
+    }
+}
 
-        let dx = dy / self.tan;
-        let wc = self.wc.translated(dx, dy);
-        self.normalized_valid_tile_position(&wc)
+//
+// Edge Crossings
+//
+impl Ray {
+    /// Distance from the origin at which the ray crosses the left edge of column `col`.
+    ///
+    /// Crossings are computed from the origin instead of from the previous crossing to avoid
+    /// accumulating rounding errors, and relative to the tile of the origin to retain precision
+    /// far away from `(0, 0)`.
+    #[allow(
+        clippy::integer_arithmetic,
+        clippy::as_conversions,
+        clippy::cast_precision_loss
+    )]
+    fn col_distance(&self, col: i64) -> f32 {
+        if self.direction_x == DirectionX::Parallel {
+            return f32::INFINITY;
+        }
+        let cols = (col - i64::from(self.tp.x)) as f32;
+        cols.mul_add(self.grid.tile_size.width, -self.tp.rel_x) / self.direction.x()
+    }
+
+    /// Like [`Ray::col_distance`] for the bottom edge of `row`.
+    #[allow(
+        clippy::integer_arithmetic,
+        clippy::as_conversions,
+        clippy::cast_precision_loss
+    )]
+    fn row_distance(&self, row: i64) -> f32 {
+        if self.direction_y == DirectionY::Parallel {
+            return f32::INFINITY;
+        }
+        let rows = (row - i64::from(self.tp.y)) as f32;
+        rows.mul_add(self.grid.tile_size.height, -self.tp.rel_y) / self.direction.y()
+    }
+
+    /// Offset of the point at the given distance from the bottom left of the current tile.
+    #[allow(
+        clippy::integer_arithmetic,
+        clippy::as_conversions,
+        clippy::cast_precision_loss
+    )]
+    fn rel_at(&self, distance: f32) -> (f32, f32) {
+        let TileSize { width, height } = self.grid.tile_size;
+        let along = |direction: f32, start: f32, tiles: i64, size: f32| {
+            if direction == 0.0 {
+                start
+            } else {
+                (tiles as f32)
+                    .mul_add(-size, direction.mul_add(distance, start))
+                    .max(0.0)
+                    .min(size)
+            }
+        };
+        (
+            along(
+                self.direction.x(),
+                self.tp.rel_x,
+                self.tile.0 - i64::from(self.tp.x),
+                width,
+            ),
+            along(
+                self.direction.y(),
+                self.tp.rel_y,
+                self.tile.1 - i64::from(self.tp.y),
+                height,
+            ),
+        )
     }
 }
 
@@ -145,9 +383,19 @@ impl Ray {
 //
 impl Ray {
     fn normalized_valid_tile_position(&self, wc: &WorldCoords) -> Option<TilePosition> {
-        let mut stp = wc.to_signed_tile_position();
+        let mut stp = wrapped(&self.grid, wc).to_signed_tile_position();
         self.normalize(&mut stp);
-        self.validated_tile_position(stp)
+        self.validated_tile_position(self.wrapped_tile(stp))
+    }
+
+    /// Moves positions normalized into the tile left of or below the grid to the opposite edge
+    /// of a wrapping grid.
+    fn wrapped_tile(&self, mut stp: SignedTilePosition) -> SignedTilePosition {
+        if self.grid.wrapping {
+            stp.x = stp.x.rem_euclid(i64::from(self.grid.cols.max(1)));
+            stp.y = stp.y.rem_euclid(i64::from(self.grid.rows.max(1)));
+        }
+        stp
     }
 
     fn validated_tile_position(&self, stp: SignedTilePosition) -> Option<TilePosition> {
@@ -165,80 +413,122 @@ impl Ray {
         }
     }
 
-    #[allow(clippy::integer_arithmetic)]
     fn normalize(&self, tp: &mut SignedTilePosition) {
-        if self.direction_x == DirectionX::Left && floats_equal(tp.rel_x, 0.0) {
-            tp.x -= 1;
-            tp.rel_x += self.grid.tile_size;
-        }
-        if self.direction_y == DirectionY::Down && floats_equal(tp.rel_y, 0.0) {
-            tp.y -= 1;
-            tp.rel_y += self.grid.tile_size;
-        }
-        normalize_zeros(tp);
+        normalize(&self.direction_x, &self.direction_y, &self.grid, tp);
     }
 }
 
 //
 // Iteration
 //
-enum Axis {
-    X,
-    Y,
-}
 
 impl Ray {
-    pub(crate) fn next_intersect(&mut self) -> Option<TilePosition> {
-        let closest_axis = match (&self.intersect_x, &self.intersect_y) {
-            (None, None) => None,
-            (None, Some(_)) => Some(Axis::Y),
-            (Some(_), None) => Some(Axis::X),
-            (Some(ref tpx), Some(ref tpy)) => {
-                let dx = self.tp.distance_global(tpx, self.grid.tile_size);
-                let dy = self.tp.distance_global(tpy, self.grid.tile_size);
-                if dx < dy {
-                    Some(Axis::X)
-                } else {
-                    Some(Axis::Y)
-                }
-            }
+    pub(crate) fn next_intersect(&mut self) -> Option<RayHit> {
+        if self.range.is_ended() {
+            return None;
+        }
+        // Crossings at the target of a segment are normalized into the tile the ray enters, which
+        // lies past the target when the target is on a left or bottom edge approached from the
+        // right or above, thus the target itself is emitted instead
+        let next = self.next_crossing();
+        if let Some(hit) = next.filter(|hit| self.range.within(hit.distance)) {
+            self.range.crossed(hit.distance);
+            return Some(hit);
+        }
+        let max_distance = self.range.finish()?;
+        let end = self.end.take()?;
+        Some(self.hit(end, None, max_distance))
+    }
+
+    /// Steps into the next tile along the ray, i.e. only the sign of the direction is needed to
+    /// step along an axis which keeps rays close to vertical or horizontal precise.
+    #[allow(clippy::integer_arithmetic)]
+    fn next_crossing(&mut self) -> Option<RayHit> {
+        if let Some(entry) = self.entry.take() {
+            return Some(entry);
+        }
+        let distance = self.distance_x.min(self.distance_y);
+        if !distance.is_finite() {
+            return None;
+        }
+        let corner = (self.distance_x - self.distance_y).abs() < CORNER_TOLERANCE;
+        let cross_x = corner || self.distance_x < self.distance_y;
+        let cross_y = corner || self.distance_y < self.distance_x;
+
+        let TileSize { width, height } = self.grid.tile_size;
+        let (step_x, step_y) = (self.step_x(), self.step_y());
+        if cross_x {
+            self.tile.0 += step_x;
+            self.next_col += step_x;
+            self.distance_x = self.col_distance(self.next_col);
+        }
+        if cross_y {
+            self.tile.1 += step_y;
+            self.next_row += step_y;
+            self.distance_y = self.row_distance(self.next_row);
+        }
+
+        // Snap onto the edges that were crossed to avoid rounding errors
+        let (mut rel_x, mut rel_y) = self.rel_at(distance);
+        if cross_x {
+            rel_x = if step_x < 0 { width } else { 0.0 };
+        }
+        if cross_y {
+            rel_y = if step_y < 0 { height } else { 0.0 };
+        }
+        let mut stp = SignedTilePosition::new(self.tile.0, self.tile.1, rel_x, rel_y);
+        normalize_zeros(&mut stp);
+        let Some(tp) = self.validated_tile_position(self.wrapped_tile(stp)) else {
+            // Left the grid
+            self.distance_x = f32::INFINITY;
+            self.distance_y = f32::INFINITY;
+            return None;
         };
-        match closest_axis {
-            Some(Axis::X) => {
-                let next = self.intersect_x.clone();
-                self.update_intersect_x();
-                next
-            }
-            Some(Axis::Y) => {
-                let next = self.intersect_y.clone();
-                self.update_intersect_y();
-                next
-            }
-            None => None,
+
+        let edge = match (cross_x, cross_y) {
+            (true, true) => self.corner_edge(),
+            (true, false) => self.vertical_edge(),
+            (false, _) => self.horizontal_edge(),
+        };
+        Some(self.hit(tp, Some(edge), distance))
+    }
+
+    const fn step_x(&self) -> i64 {
+        match self.direction_x {
+            DirectionX::Left => -1,
+            DirectionX::Right => 1,
+            DirectionX::Parallel => 0,
+        }
+    }
+
+    const fn step_y(&self) -> i64 {
+        match self.direction_y {
+            DirectionY::Down => -1,
+            DirectionY::Up => 1,
+            DirectionY::Parallel => 0,
         }
     }
 
-    fn update_intersect_x(&mut self) {
-        self.intersect_x = self.next_intersect_for(&self.intersect_x, &self.delta_x_axis_intersect);
+    const fn vertical_edge(&self) -> TileEdge {
+        match self.direction_x {
+            DirectionX::Left => TileEdge::Right,
+            DirectionX::Right | DirectionX::Parallel => TileEdge::Left,
+        }
     }
 
-    fn update_intersect_y(&mut self) {
-        self.intersect_y = self.next_intersect_for(&self.intersect_y, &self.delta_y_axis_intersect);
+    const fn horizontal_edge(&self) -> TileEdge {
+        match self.direction_y {
+            DirectionY::Down => TileEdge::Top,
+            DirectionY::Up | DirectionY::Parallel => TileEdge::Bottom,
+        }
     }
 
-    fn next_intersect_for(
-        &self,
-        intersect: &Option<TilePosition>,
-        delta: &Option<SignedTilePosition>,
-    ) -> Option<TilePosition> {
-        match (intersect, delta) {
-            (None, _) | (_, None) => None,
-            (Some(ref intersect), Some(ref delta)) => {
-                let stp = intersect + delta;
-                // convert back and forth to world coords to ensure that rel_x,rel_y <= tile_size
-                let wc = WorldCoords::from_signed_tile_position(&stp, self.grid.tile_size);
-                self.normalized_valid_tile_position(&wc)
-            }
+    const fn corner_edge(&self) -> TileEdge {
+        match (self.vertical_edge(), self.horizontal_edge()) {
+            (TileEdge::Right, TileEdge::Top) => TileEdge::TopRight,
+            (TileEdge::Right, _) => TileEdge::BottomRight,
+            (_, TileEdge::Top) => TileEdge::TopLeft,
+            (_, _) => TileEdge::BottomLeft,
         }
     }
 }
@@ -248,13 +538,13 @@ mod tests {
     #[cfg(feature = "plot")]
     use crate::plot::{plot_ray, PlotType};
 
-    use crate::util::{round_ostp, round_otp};
+    use crate::util::{round, round_otp, round_tp};
 
     use super::*;
 
     fn init_centered_3x3(angle_deg: f32) -> Ray {
         let tile_size = 1.0;
-        let grid = Grid::new(3, 3, tile_size);
+        let grid = Grid::new(3, 3, tile_size).unwrap();
         let tp = TilePosition::new(1, 1, 0.5, 0.5);
 
         Ray::new(grid, tp, angle_deg.to_radians())
@@ -342,11 +632,19 @@ mod tests {
         ];
         for (angle, x, y) in test_cases {
             let ray = init_centered_3x3(angle);
-            let Ray {
-                intersect_x,
-                intersect_y,
-                ..
-            } = ray;
+            #[cfg(feature = "plot")]
+            let (grid, tp) = (ray.grid.clone(), ray.tp.clone());
+
+            // First crossings of a vertical and a horizontal edge respectively, which are the
+            // same when passing through a corner
+            let hits: Vec<RayHit> = ray.into_iter().hits().take(2).collect();
+            let first_crossing = |excluded: [TileEdge; 2]| {
+                hits.iter()
+                    .find(|hit| hit.edge.map_or(false, |edge| !excluded.contains(&edge)))
+                    .map(|hit| hit.tp.clone())
+            };
+            let intersect_x = first_crossing([TileEdge::Bottom, TileEdge::Top]);
+            let intersect_y = first_crossing([TileEdge::Left, TileEdge::Right]);
 
             #[cfg(feature = "plot")]
             {
@@ -355,8 +653,6 @@ mod tests {
                     .flat_map(|x| x)
                     .collect();
 
-                let Ray { grid, tp, .. } = ray;
-
                 plot_ray(
                     "starting_intersections",
                     &grid,
@@ -373,66 +669,95 @@ mod tests {
     }
 
     #[test]
-    fn intersection_deltas() {
-        let test_cases: Vec<(f32, Option<SignedTilePosition>, Option<SignedTilePosition>)> = vec![
-            (0.0, Some(((1, 0.00), (0, 0.00)).into()), None),
-            (
-                30.0,
-                Some(((1, 0.00), (0, 0.577)).into()),
-                Some(((1, 0.732), (1, 0.00)).into()),
-            ),
-            (
-                45.0,
-                Some(((1, 0.00), (1, 0.000)).into()),
-                Some(((1, 0.00), (1, 0.000)).into()),
-            ),
+    fn near_axis_angles() {
+        let test_cases: Vec<(f32, TilePosition)> = vec![
+            (0.0, ((2, 0.000), (1, 0.500)).into()),
+            (90.0, ((1, 0.500), (2, 0.000)).into()),
+            (180.0, ((0, 1.000), (1, 0.500)).into()),
+            (270.0, ((1, 0.500), (0, 1.000)).into()),
+        ];
+        for (angle, expected) in test_cases {
+            for offset in &[-1e-4, -1e-6, -1e-7, 0.0, 1e-7, 1e-6, 1e-4] {
+                let grid = Grid::new(3, 3, 1.0).unwrap();
+                let tp = TilePosition::new(1, 1, 0.5, 0.5);
+                let tps: Vec<TilePosition> = Ray::new(grid, tp, angle.to_radians() + offset)
+                    .into_iter()
+                    .map(|tp| round_tp(&tp))
+                    .collect();
+                assert_eq!(tps, [expected.clone()], "{} deg {:+} rad", angle, offset);
+            }
+        }
+    }
+
+    #[test]
+    fn hit_edges() {
+        let test_cases: Vec<(f32, Vec<TileEdge>)> = vec![
+            (0.0, vec![TileEdge::Left]),
+            (30.0, vec![TileEdge::Left, TileEdge::Bottom]),
+            (45.0, vec![TileEdge::BottomLeft]),
+            (90.0, vec![TileEdge::Bottom]),
+            (150.0, vec![TileEdge::Right, TileEdge::Bottom]),
+            (180.0, vec![TileEdge::Right]),
+            (225.0, vec![TileEdge::TopRight]),
+            (270.0, vec![TileEdge::Top]),
+            (330.0, vec![TileEdge::Left, TileEdge::Top]),
+        ];
+        for (angle, edges) in test_cases {
+            let hits: Vec<Option<TileEdge>> = init_centered_3x3(angle)
+                .into_iter()
+                .hits()
+                .map(|hit| hit.edge)
+                .collect();
+            let edges: Vec<Option<TileEdge>> = edges.into_iter().map(Some).collect();
+            assert_eq!(hits, edges, "{} deg", angle);
+        }
+    }
+
+    #[test]
+    fn entering_from_outside() {
+        let grid = Grid::new(3, 3, 1.0).unwrap();
+        let test_cases: Vec<((f32, f32), f32, Option<(TilePosition, TileEdge, f32)>)> = vec![
             (
-                60.0,
-                Some(((1, 0.00), (1, 0.732)).into()),
-                Some(((0, 0.577), (1, 0.00)).into()),
+                (-1.0, 1.5),
+                0.0,
+                Some((((0, 0.0), (1, 0.5)).into(), TileEdge::Left, 1.0)),
             ),
-            (90.0, None, Some(((0, 0.00), (1, 0.00)).into())),
             (
-                135.0,
-                Some(((-1, 0.00), (1, 0.000)).into()),
-                Some(((-1, 0.00), (1, 0.000)).into()),
+                (4.5, 1.5),
+                180.0,
+                Some((((2, 1.0), (1, 0.5)).into(), TileEdge::Right, 1.5)),
             ),
             (
-                150.0,
-                Some(((-1, 0.00), (0, 0.577)).into()),
-                Some(((-1, -0.732), (1, 0.000)).into()),
+                (0.5, -2.0),
+                90.0,
+                Some((((0, 0.5), (0, 0.0)).into(), TileEdge::Bottom, 2.0)),
             ),
-            (180.0, Some(((-1, 0.00), (0, 0.00)).into()), None),
             (
-                225.0,
-                Some(((-1, 0.000), (0, -1.000)).into()),
-                Some(((-1, 0.000), (-1, 0.000)).into()),
+                (2.5, 5.0),
+                270.0,
+                Some((((2, 0.5), (2, 1.0)).into(), TileEdge::Top, 2.0)),
             ),
             (
-                210.0,
-                Some(((-1, 0.00), (0, -0.577)).into()),
-                Some(((-1, -0.732), (-1, 0.000)).into()),
+                (-1.0, -1.0),
+                45.0,
+                Some((((0, 0.0), (0, 0.0)).into(), TileEdge::BottomLeft, 1.414)),
             ),
-            (270.0, None, Some(((0, 0.00), (-1, 0.00)).into())),
             (
+                (-1.0, 4.0),
                 315.0,
-                Some(((1, 0.000), (-1, 0.000)).into()),
-                Some(((0, 1.00), (-1, 0.000)).into()),
-            ),
-            (
-                330.0,
-                Some(((1, 0.00), (0, -0.577)).into()),
-                Some(((1, 0.732), (-1, 0.000)).into()),
+                Some((((0, 0.0), (2, 1.0)).into(), TileEdge::TopLeft, 1.414)),
             ),
+            ((-1.0, 1.5), 180.0, None),
+            ((-1.0, 1.5), 90.0, None),
+            // only touches the corner of the grid
+            ((-1.0, 2.0), 45.0, None),
         ];
-        for (angle, dx, dy) in test_cases {
-            let Ray {
-                delta_x_axis_intersect: delta_x_axis_intersects,
-                delta_y_axis_intersect: delta_y_axis_intersects,
-                ..
-            } = init_centered_3x3(angle);
-            assert_eq!(round_ostp(delta_x_axis_intersects), dx);
-            assert_eq!(round_ostp(delta_y_axis_intersects), dy);
+        for ((x, y), angle, expected) in test_cases {
+            let wc = WorldCoords::new(x, y, grid.tile_size);
+            let entry = Ray::from_world(grid.clone(), &wc, angle.to_radians())
+                .and_then(|ray| ray.into_iter().hits().next())
+                .map(|hit| (round_tp(&hit.tp), hit.edge.unwrap(), round(hit.distance, 3)));
+            assert_eq!(entry, expected, "({}, {}) {} deg", x, y, angle);
         }
     }
 }
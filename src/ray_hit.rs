@@ -0,0 +1,68 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use crate::TilePosition;
+
+/// Edge of a tile through which a ray entered that tile.
+/// Rays passing exactly through the corner of a tile report that corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileEdge {
+    Left,
+    Right,
+    Bottom,
+    Top,
+    BottomLeft,
+    BottomRight,
+    TopLeft,
+    TopRight,
+}
+
+impl TileEdge {
+    /// Unit vector perpendicular to the edge pointing out of the tile, i.e. towards the ray
+    /// origin.
+    /// For corners the diagonal pointing away from the tile center is returned.
+    pub fn normal(&self) -> (f32, f32) {
+        match self {
+            Self::Left => (-1.0, 0.0),
+            Self::Right => (1.0, 0.0),
+            Self::Bottom => (0.0, -1.0),
+            Self::Top => (0.0, 1.0),
+            Self::BottomLeft => (-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+            Self::BottomRight => (FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+            Self::TopLeft => (-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            Self::TopRight => (FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        }
+    }
+
+    /// `true` if the edge is vertical, i.e. was hit when crossing a vertical grid line.
+    pub const fn is_vertical(&self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+
+    /// `true` if the edge is horizontal, i.e. was hit when crossing a horizontal grid line.
+    pub const fn is_horizontal(&self) -> bool {
+        matches!(self, Self::Bottom | Self::Top)
+    }
+
+    /// `true` if a ray passed exactly through the corner of a tile.
+    pub const fn is_corner(&self) -> bool {
+        !self.is_vertical() && !self.is_horizontal()
+    }
+}
+
+/// Intersection of a ray with a tile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RayHit {
+    /// Position at which the ray entered the tile.
+    pub tp: TilePosition,
+    /// Edge through which the ray entered the tile, `None` for the final point of a ranged ray
+    /// or segment, see [`crate::TileRaycaster::cast_ray_ranged`].
+    pub edge: Option<TileEdge>,
+    /// Distance from the ray origin in world units.
+    pub distance: f32,
+    /// Parameter in `0.0..=1.0` locating the hit along the ray, i.e. the hit is located at
+    /// `origin + t * (end - origin)`.
+    /// The end of a ray is the point at which it leaves the grid, unless it is ranged or a
+    /// segment, in which case it is the point at its max distance or the segment's target
+    /// respectively.
+    pub t: f32,
+}
@@ -1,6 +1,12 @@
-use crate::{position::TilePosition, ray::Ray};
+use crate::{position::TilePosition, ray::Ray, ray_hit::RayHit};
 
+/// Iterates over the positions at which a ray enters each tile it crosses.
 pub struct RayIter {
+    hits: RayHitIter,
+}
+
+/// Iterates over the tiles a ray crosses, see [`RayIter::hits`].
+pub struct RayHitIter {
     intersections: Ray,
     last_intersect: Option<(u32, u32)>,
 }
@@ -8,24 +14,43 @@ pub struct RayIter {
 impl Ray {
     const fn iter(self) -> RayIter {
         RayIter {
-            intersections: self,
-            last_intersect: None,
+            hits: RayHitIter {
+                intersections: self,
+                last_intersect: None,
+            },
         }
     }
 }
 
+impl RayIter {
+    /// Converts this iterator into one that includes the edge through which each tile was
+    /// entered.
+    pub const fn hits(self) -> RayHitIter {
+        self.hits
+    }
+}
+
 impl Iterator for RayIter {
     type Item = TilePosition;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        self.hits.next().map(|hit| hit.tp)
+    }
+}
+
+impl Iterator for RayHitIter {
+    type Item = RayHit;
+
     fn next(&mut self) -> Option<Self::Item> {
         let next_intersect = self.intersections.next_intersect()?;
 
-        // Ensure that we don't emit the same tile position twice which could happen if
-        // x and y intersections are the same, i.e. for a 45 deg angle
-        let TilePosition { x, y, .. } = next_intersect;
+        // Ensure that we don't emit the same tile position twice.
+        // The end of a ranged ray is always emitted even if it lies in the same tile as the
+        // last intersection.
+        let TilePosition { x, y, .. } = next_intersect.tp;
         let next_intersect_x_y = Some((x, y));
 
-        if next_intersect_x_y == self.last_intersect {
+        if next_intersect_x_y == self.last_intersect && next_intersect.edge.is_some() {
             self.next()
         } else {
             self.last_intersect = next_intersect_x_y;
@@ -0,0 +1,76 @@
+// Intersections closer than this to the end of a ranged ray are considered to be located at its
+// end.
+const END_TOLERANCE: f32 = 1e-5;
+
+/// Tracks the distance a ray travelled in order to stop it at its maximum distance, shared by
+/// the rays cast across all kinds of grids.
+///
+/// Rays without a maximum distance only end once they are stopped, e.g. when leaving the grid.
+#[derive(Debug, Clone, Default)]
+pub struct RayRange {
+    max_distance: Option<f32>,
+    // Segments always end exactly at their target, even if a crossing lies on it
+    exact: bool,
+    last_distance: f32,
+    ended: bool,
+}
+
+impl RayRange {
+    /// Range of a ray which ends at the point `max_distance` away from its origin unless that
+    /// point is the last crossing already.
+    pub const fn new(max_distance: f32) -> Self {
+        Self {
+            max_distance: Some(max_distance),
+            exact: false,
+            last_distance: 0.0,
+            ended: false,
+        }
+    }
+
+    /// Range of a segment which always ends at its target `length` away from its origin,
+    /// crossings at the target itself are dropped in favor of it.
+    pub const fn exact(length: f32) -> Self {
+        Self {
+            exact: true,
+            ..Self::new(length)
+        }
+    }
+
+    pub const fn is_ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Returns `true` if a crossing at the given distance is still part of the ray.
+    pub fn within(&self, distance: f32) -> bool {
+        match self.max_distance {
+            Some(max_distance) if self.exact => max_distance - distance >= END_TOLERANCE,
+            Some(max_distance) => distance <= max_distance,
+            None => true,
+        }
+    }
+
+    /// Records the crossing at the given distance, see [`RayRange::within`].
+    pub fn crossed(&mut self, distance: f32) {
+        self.last_distance = distance;
+    }
+
+    /// Ends the ray once the next crossing lies beyond its range.
+    /// Returns the distance of the point at which it ends, unless there is none or it was
+    /// emitted as the last crossing already.
+    pub fn finish(&mut self) -> Option<f32> {
+        self.ended = true;
+        let max_distance = self.max_distance?;
+        let at_last_crossing =
+            self.last_distance > 0.0 && max_distance - self.last_distance < END_TOLERANCE;
+        if self.exact || !at_last_crossing {
+            Some(max_distance)
+        } else {
+            None
+        }
+    }
+
+    /// Ends the ray without emitting an end point, e.g. once it left the grid.
+    pub fn stop(&mut self) {
+        self.ended = true;
+    }
+}
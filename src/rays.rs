@@ -1,7 +1,7 @@
 use std::f32::consts::PI;
 
 use crate::{
-    angle::{DirectionX, DirectionY},
+    angle::{Direction, DirectionX, DirectionY},
     position::WorldCoords,
     ray::Ray,
     util::round,
@@ -10,9 +10,79 @@ use crate::{
 
 const RAY_PRECISION: usize = 8;
 
-pub fn rays_from(center: &TilePosition, grid: &Grid, width: f32, angle: &AngleRad) -> Vec<Ray> {
+/// Ray cast as part of a beam, see [`crate::TileRaycaster::beam_layout`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeamRay {
+    /// Absolute world coordinates of the point the ray is cast from, see [`Grid::with_origin`].
+    /// It lies outside of the grid for rays near the side of a beam that only partially covers
+    /// it.
+    pub origin: (f32, f32),
+    /// Position at which the ray starts on the grid, i.e. its origin or the point at which it
+    /// enters the grid.
+    pub start: TilePosition,
+    /// Signed distance of the origin from the center of the beam across its width, positive to
+    /// the left of its direction.
+    pub offset: f32,
+    /// Direction in which the ray is cast, which is the same for all rays of the beam.
+    pub direction: Direction,
+}
+
+/// Rays cast for a beam, indexed like the [`crate::BeamIntersect`]s of that beam.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeamLayout {
+    /// Distance between the origins of neighboring rays across the width of the beam.
+    pub spacing: f32,
+    pub rays: Vec<BeamRay>,
+}
+
+pub fn rays_from(
+    center: &TilePosition,
+    grid: &Grid,
+    width: f32,
+    direction: &Direction,
+) -> Vec<Ray> {
+    let (_, rays) = spaced_rays_from(center, grid, width, direction);
+    rays.into_iter().map(|(_, ray)| ray).collect()
+}
+
+/// Describes the rays [`rays_from`] casts for the same beam.
+pub fn beam_layout_from(
+    center: &TilePosition,
+    grid: &Grid,
+    width: f32,
+    direction: &Direction,
+) -> BeamLayout {
+    let center_wc = WorldCoords::from_tile_position(center, grid.tile_size);
+    let (spacing, rays) = spaced_rays_from(center, grid, width, direction);
+    let rays = rays
+        .into_iter()
+        .map(|(origin, ray)| {
+            // Project onto the normal pointing to the left of the direction
+            let (dx, dy) = (origin.x() - center_wc.x(), origin.y() - center_wc.y());
+            let offset = direction.x().mul_add(dy, -direction.y() * dx);
+            BeamRay {
+                origin: (origin.x() + grid.origin_x, origin.y() + grid.origin_y),
+                start: ray.tp,
+                offset,
+                direction: direction.clone(),
+            }
+        })
+        .collect();
+    BeamLayout { spacing, rays }
+}
+
+/// Rays of the beam together with their origins and the spacing between them.
+/// Rays whose origin lies outside of the grid start where they enter it and are omitted if
+/// they never do.
+fn spaced_rays_from(
+    center: &TilePosition,
+    grid: &Grid,
+    width: f32,
+    direction: &Direction,
+) -> (f32, Vec<(WorldCoords, Ray)>) {
     debug_assert!(width > 0.0, "width needs to be > 0");
 
+    let angle = direction.angle();
     let center_wc = WorldCoords::from_tile_position(center, grid.tile_size);
 
     let left_rad: AngleRad = angle.perpendicular();
@@ -22,7 +92,7 @@ pub fn rays_from(center: &TilePosition, grid: &Grid, width: f32, angle: &AngleRa
     );
 
     // sections on each side
-    let sections = (width.ceil() / grid.tile_size.floor()).max(1.0).ceil();
+    let sections = (width.ceil() / grid.tile_size.min()).max(1.0).ceil();
     let section_width = (width / 2.0) / sections;
     #[allow(
         clippy::as_conversions,
@@ -51,17 +121,48 @@ pub fn rays_from(center: &TilePosition, grid: &Grid, width: f32, angle: &AngleRa
         )
     };
     #[allow(clippy::integer_arithmetic)]
-    (-sections..=sections)
+    let rays = (-sections..=sections)
         .filter_map(|idx| {
             let len = section_width * f32::from(idx);
             let dx = left_sin * len * fx;
             let dy = left_cos * len * fy;
-            let tp = center_wc
-                .translated(dx, dy)
-                .bounds_checked(grid)?
-                .to_tile_position()
-                .ok()?;
-            Some(Ray::new(grid.clone(), tp, angle.clone()))
+            let origin = center_wc.translated(dx, dy);
+            let ray = Ray::from_world(grid.clone(), &origin, direction.clone())?;
+            Some((origin, ray))
+        })
+        .collect();
+    (section_width, rays)
+}
+
+/// Fans out rays from the `origin` across the cone of `half_angle` to either side of the
+/// `direction`, starting with the one furthest counter-clockwise.
+/// Enough rays are cast so that neighboring rays are less than half a tile apart at `reach`.
+pub fn cone_rays_from(
+    origin: &TilePosition,
+    grid: &Grid,
+    direction: &Direction,
+    half_angle: &AngleRad,
+    reach: f32,
+) -> Vec<Ray> {
+    debug_assert!(half_angle.0 >= 0.0, "half angle needs to be >= 0");
+
+    let center = direction.angle();
+    let max_spacing = grid.tile_size.min() / 2.0 / reach.max(f32::EPSILON);
+    // sections on each side
+    let sections = (half_angle.0 / max_spacing)
+        .ceil()
+        .max(1.0)
+        .min(f32::from(i16::MAX));
+    let section_angle = half_angle.0 / sections;
+    #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+    let sections = sections as i16;
+
+    #[allow(clippy::integer_arithmetic)]
+    (-sections..=sections)
+        .rev()
+        .map(|idx| {
+            let angle = AngleRad(section_angle.mul_add(f32::from(idx), center.0));
+            Ray::new(grid.clone(), origin.clone(), &angle)
         })
         .collect()
 }
@@ -98,8 +199,8 @@ mod tests {
     #[test]
     fn rays_from_width_smaller_than_tile_isolate() {
         let center = TilePosition::new(1, 1, 0.5, 0.5);
-        let grid = Grid::new(4, 4, 1.0);
-        let width = grid.tile_size * 0.8;
+        let grid = Grid::new(4, 4, 1.0).unwrap();
+        let width = grid.tile_size.width * 0.8;
 
         // Right/Down at 315
         let angle = 315_f32.to_radians();
@@ -112,11 +213,29 @@ mod tests {
             ]
         );
     }
+    #[test]
+    fn rays_from_tiles_smaller_than_one() {
+        let center = TilePosition::new(2, 2, 0.25, 0.25);
+        let grid = Grid::new(8, 8, 0.5).unwrap();
+
+        // To the right, neighboring rays are less than half a tile apart
+        assert_eq!(
+            rays_for_angle(&center, &grid, 0.8, 0.0),
+            [
+                ((2, 0.250), (3, 0.150)).into(),
+                ((2, 0.250), (2, 0.450)).into(),
+                ((2, 0.250), (2, 0.250)).into(),
+                ((2, 0.250), (2, 0.050)).into(),
+                ((2, 0.250), (1, 0.350)).into()
+            ],
+        );
+    }
+
     #[test]
     fn rays_from_width_smaller_than_tile() {
         let center = TilePosition::new(1, 1, 0.5, 0.5);
-        let grid = Grid::new(4, 4, 1.0);
-        let width = grid.tile_size * 0.8;
+        let grid = Grid::new(4, 4, 1.0).unwrap();
+        let width = grid.tile_size.width * 0.8;
 
         // To the right
         let angle = 0.0;
@@ -241,13 +360,14 @@ mod tests {
             ]
         );
 
+        // Angles are converted into directions, thus 361 is the same as 1
         let angle = 361_f32.to_radians();
         assert_eq!(
             rays_for_angle(&center, &grid, width, angle),
             [
-                ((1, 0.507), (1, 0.100)).into(),
+                ((1, 0.493), (1, 0.900)).into(),
                 ((1, 0.500), (1, 0.500)).into(),
-                ((1, 0.493), (1, 0.900)).into()
+                ((1, 0.507), (1, 0.100)).into()
             ]
         );
     }
@@ -255,8 +375,8 @@ mod tests {
     #[test]
     fn rays_from_width_same_as_tile() {
         let center = TilePosition::new(1, 1, 0.5, 0.5);
-        let grid = Grid::new(4, 4, 1.0);
-        let width = grid.tile_size;
+        let grid = Grid::new(4, 4, 1.0).unwrap();
+        let width = grid.tile_size.width;
 
         let angle = 0.0;
         assert_eq!(
@@ -282,8 +402,8 @@ mod tests {
     #[test]
     fn rays_from_width_larger_than_tile() {
         let center = TilePosition::new(1, 1, 0.5, 0.5);
-        let grid = Grid::new(4, 4, 1.0);
-        let width = grid.tile_size * 2.0;
+        let grid = Grid::new(4, 4, 1.0).unwrap();
+        let width = grid.tile_size.width * 2.0;
 
         let angle = 0.0;
         assert_eq!(
@@ -312,11 +432,11 @@ mod tests {
 
     #[test]
     fn rays_bounds() {
-        let grid = Grid::new(4, 4, 1.0);
+        let grid = Grid::new(4, 4, 1.0).unwrap();
 
         let angle = 0.0;
         let center = TilePosition::new(0, 0, 0.0, 0.0);
-        let width = grid.tile_size * 0.8;
+        let width = grid.tile_size.width * 0.8;
         assert_eq!(
             rays_for_angle(&center, &grid, width, angle),
             [
@@ -326,7 +446,7 @@ mod tests {
         );
 
         let center = TilePosition::new(1, 1, 0.5, 0.5);
-        let width = grid.tile_size * 10.0;
+        let width = grid.tile_size.width * 10.0;
         assert_eq!(
             rays_for_angle(&center, &grid, width, angle),
             [
@@ -343,7 +463,7 @@ mod tests {
 
         let angle = 90_f32.to_radians();
         let center = TilePosition::new(2, 2, 0.5, 0.5);
-        let width = grid.tile_size * 10.0;
+        let width = grid.tile_size.width * 10.0;
         assert_eq!(
             rays_for_angle(&center, &grid, width, angle),
             [
@@ -358,18 +478,25 @@ mod tests {
             ]
         );
 
+        // Rays originating left of and above the grid enter it through its left and top edges
         let angle = 315_f32.to_radians();
         let center = TilePosition::new(0, 2, 0.5, 0.5);
-        let width = grid.tile_size * 10.0;
+        let width = grid.tile_size.width * 10.0;
         assert_eq!(
             rays_for_angle(&center, &grid, width, angle),
             [
+                ((0, 0.000), (0, 0.172)).into(),
+                ((0, 0.000), (0, 0.879)).into(),
+                ((0, 0.000), (1, 0.586)).into(),
                 ((0, 0.146), (2, 0.146)).into(),
                 ((0, 0.500), (2, 0.500)).into(),
                 ((0, 0.854), (2, 0.854)).into(),
                 ((1, 0.207), (3, 0.207)).into(),
                 ((1, 0.561), (3, 0.561)).into(),
-                ((1, 0.914), (3, 0.914)).into()
+                ((1, 0.914), (3, 0.914)).into(),
+                ((2, 0.536), (3, 1.000)).into(),
+                ((3, 0.243), (3, 1.000)).into(),
+                ((3, 0.950), (3, 1.000)).into()
             ]
         );
     }
@@ -0,0 +1,303 @@
+use std::{cmp::Ordering, convert::TryFrom};
+
+use crate::{angle::Direction, BitGrid, Grid, TilePosition};
+
+// Tiles whose overlap with the beam is thinner than this (relative to the tile size) merely
+// touch its sides and aren't considered to be covered by it.
+const THICKNESS_TOLERANCE: f32 = 1e-5;
+
+// Corners of the covered part of a tile closer than this to the front of the beam are
+// considered to be touched by it at the same time.
+const CONTACT_TOLERANCE: f32 = 1e-5;
+
+type Point = (f32, f32);
+
+/// Tile covered by a swept beam, see [`crate::TileRaycaster::cast_beam_swept`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweptTile {
+    /// Point at which the front of the beam first touches the tile.
+    pub tp: TilePosition,
+    /// Distance the beam travelled before touching the tile.
+    pub distance: f32,
+}
+
+/// Iterates over all tiles covered by a swept beam in order of distance.
+pub struct SweptBeamIter {
+    tiles: std::vec::IntoIter<SweptTile>,
+}
+
+impl Iterator for SweptBeamIter {
+    type Item = SweptTile;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tiles.next()
+    }
+}
+
+/// Rectangle covered by a line of `width` centered on `center` and perpendicular to
+/// `direction` while moving `length` world units along that direction.
+struct SweptArea {
+    center: Point,
+    direction: Point,
+    length: f32,
+    half_width: f32,
+}
+
+impl SweptArea {
+    /// Distance along the direction and offset to the side of the point relative to the center.
+    fn project(&self, (x, y): Point) -> (f32, f32) {
+        let (dx, dy) = (x - self.center.0, y - self.center.1);
+        let (cos, sin) = self.direction;
+        (cos.mul_add(dx, sin * dy), cos.mul_add(dy, -sin * dx))
+    }
+
+    /// Inverse of [`SweptArea::project`].
+    fn point(&self, along: f32, side: f32) -> Point {
+        let (cx, cy) = self.center;
+        let (cos, sin) = self.direction;
+        (
+            cos.mul_add(along, -sin * side) + cx,
+            sin.mul_add(along, cos * side) + cy,
+        )
+    }
+
+    fn polygon(&self) -> Vec<Point> {
+        vec![
+            self.point(0.0, -self.half_width),
+            self.point(self.length, -self.half_width),
+            self.point(self.length, self.half_width),
+            self.point(0.0, self.half_width),
+        ]
+    }
+
+    /// Distance at which the front of the beam first touches the part of the area given by the
+    /// polygon and the point at which it does so.
+    /// If the front touches an edge of the polygon the point closest to the center line of the
+    /// beam is used.
+    fn contact(&self, polygon: &[Point]) -> Option<(f32, Point)> {
+        let projected: Vec<(f32, f32)> = polygon.iter().map(|point| self.project(*point)).collect();
+        let distance = projected
+            .iter()
+            .map(|(along, _)| *along)
+            .fold(f32::INFINITY, f32::min);
+        if !distance.is_finite() {
+            return None;
+        }
+        let (min_side, max_side) = projected
+            .iter()
+            .filter(|(along, _)| along - distance < CONTACT_TOLERANCE)
+            .fold(
+                (f32::INFINITY, f32::NEG_INFINITY),
+                |(min, max), (_, side)| (min.min(*side), max.max(*side)),
+            );
+        let side = 0.0_f32.max(min_side).min(max_side);
+        Some((distance, self.point(distance, side)))
+    }
+}
+
+/// Computes all tiles overlapped by the beam of `width` cast from `center` along the
+/// `direction` up to `max_distance` or until it leaves the grid, ordered by the distance at
+/// which the beam first touches them.
+/// On wrapping grids the beam never leaves the grid, thus `max_distance` needs to be finite
+/// and the work grows with it.
+///
+/// Rows of tiles are found by clipping the swept rectangle to each row and the tiles inside it
+/// by clipping the result to each column, thus no tile is skipped regardless of the direction.
+#[allow(
+    clippy::integer_arithmetic,
+    clippy::as_conversions,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss
+)]
+pub fn swept_tiles(
+    grid: &Grid,
+    center: &TilePosition,
+    width: f32,
+    direction: &Direction,
+    max_distance: Option<f32>,
+) -> Vec<SweptTile> {
+    let (tile_width, tile_height) = (grid.tile_size.width, grid.tile_size.height);
+    let center = (
+        (center.x as f32).mul_add(tile_width, center.rel_x),
+        (center.y as f32).mul_add(tile_height, center.rel_y),
+    );
+    let mut area = SweptArea {
+        center,
+        direction: (direction.x(), direction.y()),
+        length: 0.0,
+        half_width: width / 2.0,
+    };
+    // Far enough for the beam to cover the entire grid in front of it
+    let (grid_width, grid_height) = (grid.width as f32, grid.height as f32);
+    let reach = [
+        (0.0, 0.0),
+        (grid_width, 0.0),
+        (0.0, grid_height),
+        (grid_width, grid_height),
+    ]
+    .iter()
+    .map(|corner| area.project(*corner).0)
+    .fold(0.0, f32::max);
+    area.length = match max_distance {
+        Some(max_distance) if grid.wrapping => max_distance,
+        Some(max_distance) => max_distance.min(reach),
+        None => reach,
+    };
+    let polygon = area.polygon();
+    let min_thickness = THICKNESS_TOLERANCE * tile_width.max(tile_height);
+
+    let mut tiles = vec![];
+    let (min_row, max_row) = clamped(
+        tile_range(&polygon, |(_, y)| y, tile_height),
+        grid.rows,
+        grid.wrapping,
+    );
+    for row in min_row..=max_row {
+        let Some(y) = wrapped_index(row, grid.rows, grid.wrapping) else {
+            continue;
+        };
+        let (bottom, top) = (row as f32 * tile_height, (row + 1) as f32 * tile_height);
+        let strip = clipped(&clipped(&polygon, |(_, y)| y - bottom), |(_, y)| top - y);
+        let (min_col, max_col) = clamped(
+            tile_range(&strip, |(x, _)| x, tile_width),
+            grid.cols,
+            grid.wrapping,
+        );
+        for col in min_col..=max_col {
+            let Some(x) = wrapped_index(col, grid.cols, grid.wrapping) else {
+                continue;
+            };
+            let (left, right) = (col as f32 * tile_width, (col + 1) as f32 * tile_width);
+            let tile = clipped(&clipped(&strip, |(x, _)| x - left), |(x, _)| right - x);
+            if thickness(&tile, (left, bottom)) <= min_thickness {
+                continue;
+            }
+            if let Some((distance, (px, py))) = area.contact(&tile) {
+                let tp = TilePosition::new(
+                    x,
+                    y,
+                    (px - left).max(0.0).min(tile_width),
+                    (py - bottom).max(0.0).min(tile_height),
+                );
+                tiles.push(SweptTile {
+                    tp,
+                    distance: if distance < CONTACT_TOLERANCE {
+                        0.0
+                    } else {
+                        distance
+                    },
+                });
+            }
+        }
+    }
+    tiles.sort_by(|t1, t2| {
+        t1.distance
+            .partial_cmp(&t2.distance)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    if grid.wrapping {
+        // Beams that lap a wrapping grid cover tiles more than once
+        let mut seen = BitGrid::new(grid);
+        tiles.retain(|SweptTile { tp, .. }| {
+            let first = !seen.get(tp.x, tp.y);
+            seen.set(tp.x, tp.y, true);
+            first
+        });
+    }
+    tiles
+}
+
+impl SweptBeamIter {
+    pub(crate) fn new(tiles: Vec<SweptTile>) -> Self {
+        Self {
+            tiles: tiles.into_iter(),
+        }
+    }
+}
+
+/// Index of the tile in the grid, wrapped around on wrapping grids.
+fn wrapped_index(idx: i64, count: u32, wrapping: bool) -> Option<u32> {
+    let idx = if wrapping {
+        idx.rem_euclid(i64::from(count.max(1)))
+    } else {
+        idx
+    };
+    u32::try_from(idx).ok().filter(|idx| *idx < count)
+}
+
+/// First and last index of the tiles the polygon extends into along one axis.
+#[allow(
+    clippy::integer_arithmetic,
+    clippy::as_conversions,
+    clippy::cast_possible_truncation
+)]
+fn tile_range<F: Fn(Point) -> f32>(polygon: &[Point], coord: F, size: f32) -> (i64, i64) {
+    let (min, max) = polygon
+        .iter()
+        .map(|point| coord(*point))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), c| {
+            (min.min(c), max.max(c))
+        });
+    if min > max {
+        // Empty polygon, results in an empty range
+        return (0, -1);
+    }
+    ((min / size).floor() as i64, (max / size).ceil() as i64 - 1)
+}
+
+/// Limits the range of tile indexes to the grid unless it is wrapping.
+#[allow(clippy::integer_arithmetic)]
+fn clamped((min, max): (i64, i64), count: u32, wrapping: bool) -> (i64, i64) {
+    if wrapping {
+        (min, max)
+    } else {
+        (min.max(0), max.min(i64::from(count) - 1))
+    }
+}
+
+/// Clips a convex polygon to the half-plane in which `inside` is positive.
+#[allow(clippy::integer_arithmetic)]
+fn clipped<F: Fn(Point) -> f32>(polygon: &[Point], inside: F) -> Vec<Point> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    let Some(mut previous) = polygon.last().copied() else {
+        return clipped;
+    };
+    for current in polygon.iter().copied() {
+        let (previous_inside, current_inside) = (inside(previous), inside(current));
+        if (previous_inside >= 0.0) != (current_inside >= 0.0) {
+            let t = previous_inside / (previous_inside - current_inside);
+            clipped.push((
+                (current.0 - previous.0).mul_add(t, previous.0),
+                (current.1 - previous.1).mul_add(t, previous.1),
+            ));
+        }
+        if current_inside >= 0.0 {
+            clipped.push(current);
+        }
+        previous = current;
+    }
+    clipped
+}
+
+/// Area of the polygon divided by its diameter, which is close to zero for polygons that
+/// degenerated into a line or point.
+/// Coordinates are taken relative to `origin` to limit rounding errors.
+fn thickness(polygon: &[Point], (ox, oy): Point) -> f32 {
+    let local: Vec<Point> = polygon.iter().map(|(x, y)| (x - ox, y - oy)).collect();
+    let next = local.iter().cycle().skip(1);
+    let twice_area: f32 = local
+        .iter()
+        .zip(next)
+        .map(|((x1, y1), (x2, y2))| x1.mul_add(*y2, -x2 * y1))
+        .sum();
+    let diameter = local
+        .iter()
+        .flat_map(|(x1, y1)| local.iter().map(move |(x2, y2)| (x2 - x1).hypot(y2 - y1)))
+        .fold(0.0, f32::max);
+    if diameter > 0.0 {
+        twice_area.abs() / 2.0 / diameter
+    } else {
+        0.0
+    }
+}
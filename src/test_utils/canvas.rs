@@ -54,13 +54,13 @@ impl Canvas {
 
     fn draw_grid(&mut self, color: Color) {
         for row in 0..self.grid.rows {
-            let y = row as f32 * self.grid.tile_size;
+            let y = row as f32 * self.grid.tile_size.height;
             let start = (0.0, y);
             let end = (self.grid.width as f32, y);
             self.draw_line(start, end, color);
         }
         for col in 0..self.grid.cols {
-            let x = col as f32 * self.grid.tile_size;
+            let x = col as f32 * self.grid.tile_size.width;
             let start = (x, 0.0);
             let end = (x, self.grid.height as f32);
             self.draw_line(start, end, color);
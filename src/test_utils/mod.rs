@@ -1,2 +1,3 @@
 pub mod canvas;
 pub mod plot;
+pub mod render;
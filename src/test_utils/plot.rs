@@ -81,8 +81,8 @@ pub fn plot_rays_origins(
     for ray in rays {
         canvas.plot_tile_position(&ray.tp, BLUE);
         if let Some(next) = ray.next_intersect() {
-            canvas.plot_tile_position(&next, GRAY);
-            canvas.plot_line(&ray.tp, &next, LIGHT_GRAY);
+            canvas.plot_tile_position(&next.tp, GRAY);
+            canvas.plot_line(&ray.tp, &next.tp, LIGHT_GRAY);
         }
     }
 
@@ -103,7 +103,7 @@ pub fn plot_beam(
     beam_intersects: &Vec<BeamIntersect>,
     plot_type: PlotType,
 ) {
-    let mut rays: Vec<Ray> = rays_from(center, &grid, width, &angle);
+    let mut rays: Vec<Ray> = rays_from(center, &grid, width, &angle.into());
     let mut canvas = plot_rays_origins(grid, center, width, angle, &mut rays, PlotType::Memory);
 
     for BeamIntersect(ray_idx, tp) in beam_intersects {
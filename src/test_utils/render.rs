@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use image::{Rgb, RgbImage};
+
+use crate::{
+    canvas::{DARK_GRAY, GRAY},
+    columns::{cast_columns, Camera, ColumnHit},
+    Grid, TileEdge, TilePosition,
+};
+
+/// Appearance of a wall tile.
+pub enum Surface {
+    Solid(Rgb<u8>),
+    /// Texture stretched across each face of the tile.
+    Texture(RgbImage),
+}
+
+impl Surface {
+    fn color_at(&self, u: f32, v: f32) -> Rgb<u8> {
+        match self {
+            Surface::Solid(color) => *color,
+            Surface::Texture(texture) => {
+                let x = texel(u, texture.width());
+                let y = texel(v, texture.height());
+                *texture.get_pixel(x, y)
+            }
+        }
+    }
+}
+
+fn texel(coord: f32, size: u32) -> u32 {
+    ((coord * size as f32) as u32).min(size.saturating_sub(1))
+}
+
+/// Faces hit when crossing horizontal grid lines are drawn darker, which makes corners
+/// between walls visible when they share a surface.
+fn shade(color: Rgb<u8>, edge: TileEdge) -> Rgb<u8> {
+    if edge.is_vertical() {
+        color
+    } else {
+        let Rgb([r, g, b]) = color;
+        Rgb([r / 2, g / 2, b / 2])
+    }
+}
+
+/// Offscreen first-person renderer drawing one column per ray cast from a [`Camera`].
+pub struct Renderer {
+    pub width: u32,
+    pub height: u32,
+    pub ceiling: Rgb<u8>,
+    pub floor: Rgb<u8>,
+}
+
+impl Renderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            ceiling: DARK_GRAY,
+            floor: GRAY,
+        }
+    }
+
+    /// Renders the view of the camera into an image.
+    /// Tiles for which `surface_at` returns a surface are walls, all others are empty.
+    /// The surface of each wall is resolved once while casting the columns.
+    pub fn render<'a, F>(&self, grid: &Grid, camera: &Camera, surface_at: F) -> RgbImage
+    where
+        F: Fn(&TilePosition) -> Option<&'a Surface>,
+    {
+        let mut image = RgbImage::from_pixel(self.width, self.height, self.floor);
+        let mut surfaces = HashMap::new();
+        let columns = cast_columns(grid, camera, self.width, |tp| match surface_at(tp) {
+            Some(surface) => {
+                surfaces.insert((tp.x, tp.y), surface);
+                true
+            }
+            None => false,
+        });
+        for (x, column) in columns.iter().enumerate() {
+            let x = x as u32;
+            let wall = column
+                .hit
+                .as_ref()
+                .and_then(|hit| Some((hit, *surfaces.get(&(hit.tp.x, hit.tp.y))?)));
+            match wall {
+                Some((hit, surface)) => self.draw_wall(&mut image, x, hit, surface),
+                None => self.draw_wall_span(&mut image, x, 0.0, |_| None),
+            }
+        }
+        image
+    }
+
+    fn draw_wall(&self, image: &mut RgbImage, x: u32, hit: &ColumnHit, surface: &Surface) {
+        self.draw_wall_span(image, x, hit.wall_height, |v| {
+            Some(shade(surface.color_at(hit.texture_u, v), hit.edge))
+        });
+    }
+
+    /// Draws ceiling, wall and floor of the column at `x`, the wall being centered vertically.
+    /// `wall_color` is called with the vertical texture coordinate of each wall pixel.
+    fn draw_wall_span<C>(&self, image: &mut RgbImage, x: u32, wall_height: f32, wall_color: C)
+    where
+        C: Fn(f32) -> Option<Rgb<u8>>,
+    {
+        let top = (self.height as f32 - wall_height) / 2.0;
+        for y in 0..self.height {
+            let center = y as f32 + 0.5;
+            let color = if center < top {
+                Some(self.ceiling)
+            } else if center < top + wall_height {
+                wall_color((center - top) / wall_height)
+            } else {
+                None
+            };
+            if let Some(color) = color {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
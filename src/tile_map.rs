@@ -0,0 +1,144 @@
+use crate::{Grid, TilePosition};
+
+const BITS_PER_BLOCK: u64 = 64;
+
+/// Occupancy information that a [`crate::TileRaycaster`] can query directly instead of
+/// being handed a predicate on every call.
+pub trait TileMap {
+    /// Returns `true` if the tile at the given position stops rays passing through it.
+    fn is_blocking(&self, tp: &TilePosition) -> bool;
+}
+
+/// Dense tile map storing a single bit per tile of a [`Grid`].
+///
+/// Tiles outside of the grid are never set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    cols: u32,
+    rows: u32,
+    blocks: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Creates a map sized to the `cols` and `rows` of the provided grid with all bits cleared.
+    #[must_use]
+    #[allow(clippy::integer_arithmetic, clippy::integer_division)]
+    pub fn new(grid: &Grid) -> Self {
+        let tiles = u64::from(grid.cols) * u64::from(grid.rows);
+        // Rounded up to hold the bits of all tiles
+        let len = (tiles + BITS_PER_BLOCK - 1) / BITS_PER_BLOCK;
+        #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+        let blocks = vec![0; len as usize];
+        Self {
+            cols: grid.cols,
+            rows: grid.rows,
+            blocks,
+        }
+    }
+
+    pub const fn cols(&self) -> u32 {
+        self.cols
+    }
+
+    pub const fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Returns `true` if the bit for the tile at `x`, `y` is set.
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        self.locate(x, y)
+            .and_then(|(block, mask)| self.blocks.get(block).map(|bits| bits & mask != 0))
+            .unwrap_or(false)
+    }
+
+    /// Sets or clears the bit for the tile at `x`, `y`.
+    /// Positions outside of the grid are ignored.
+    pub fn set(&mut self, x: u32, y: u32, value: bool) {
+        if let Some((block, mask)) = self.locate(x, y) {
+            if let Some(bits) = self.blocks.get_mut(block) {
+                if value {
+                    *bits |= mask;
+                } else {
+                    *bits &= !mask;
+                }
+            }
+        }
+    }
+
+    /// Clears all bits.
+    pub fn clear(&mut self) {
+        for bits in &mut self.blocks {
+            *bits = 0;
+        }
+    }
+
+    /// Number of tiles whose bit is set.
+    pub fn count(&self) -> u32 {
+        self.blocks.iter().map(|bits| bits.count_ones()).sum()
+    }
+
+    /// Iterates over the `(x, y)` coordinates of all tiles whose bit is set, row by row
+    /// starting at the bottom left.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let cols = self.cols;
+        (0..self.rows)
+            .flat_map(move |y| (0..cols).map(move |x| (x, y)))
+            .filter(move |(x, y)| self.get(*x, *y))
+    }
+
+    #[allow(clippy::integer_arithmetic, clippy::integer_division)]
+    fn locate(&self, x: u32, y: u32) -> Option<(usize, u64)> {
+        if x >= self.cols || y >= self.rows {
+            return None;
+        }
+        let idx = u64::from(y) * u64::from(self.cols) + u64::from(x);
+        let block = idx / BITS_PER_BLOCK;
+        let mask = 1_u64 << (idx % BITS_PER_BLOCK);
+        #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+        Some((block as usize, mask))
+    }
+}
+
+impl TileMap for BitGrid {
+    fn is_blocking(&self, tp: &TilePosition) -> bool {
+        self.get(tp.x, tp.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_get() {
+        let grid = Grid::new(10, 7, 1.0).unwrap();
+        let mut map = BitGrid::new(&grid);
+        assert_eq!(map.count(), 0);
+
+        for (x, y) in vec![(0, 0), (9, 0), (3, 4), (9, 6)] {
+            map.set(x, y, true);
+            assert!(map.get(x, y));
+        }
+        assert_eq!(map.count(), 4);
+        assert!(!map.get(1, 0));
+        assert!(!map.get(0, 1));
+
+        map.set(3, 4, false);
+        assert!(!map.get(3, 4));
+        assert_eq!(map.iter().collect::<Vec<_>>(), [(0, 0), (9, 0), (9, 6)]);
+
+        map.clear();
+        assert_eq!(map.count(), 0);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let grid = Grid::new(3, 3, 1.0).unwrap();
+        let mut map = BitGrid::new(&grid);
+        map.set(3, 0, true);
+        map.set(0, 3, true);
+        assert_eq!(map.count(), 0);
+        assert!(!map.get(3, 0));
+        assert!(!map.is_blocking(&TilePosition::new(0, 3, 0.0, 0.0)));
+    }
+}
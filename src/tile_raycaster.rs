@@ -1,125 +1,938 @@
 use crate::{
-    beam::Beam, beam_iter::BeamIter, grid::Grid, position::TilePosition, ray::Ray,
-    ray_iter::RayIter, rays::rays_from, AngleRad, BeamIntersect,
+    angle::{validated_angle, validated_direction, validated_half_angle, Direction},
+    beam::Beam,
+    beam_iter::{BeamIter, OccludedBeamIter},
+    columns::{cast_columns, Camera, Column},
+    fov::field_of_view,
+    grid::Grid,
+    position::{TilePosition, WorldCoords},
+    ray::Ray,
+    ray_iter::RayIter,
+    rays::{beam_layout_from, cone_rays_from, rays_from, BeamLayout},
+    swept_beam::{swept_tiles, SweptBeamIter},
+    tile_map::TileMap,
+    util::validated_distance,
+    AngleRad, BeamHit, BeamIntersect, BitGrid, CrissCrossError, CrissCrossResult, FovAlgorithm,
 };
 
-#[derive(Debug, Default, PartialEq)]
-pub struct Crossing {
-    pub valid: Option<TilePosition>,
-    pub invalid: Option<TilePosition>,
+/// Last valid and first invalid position along a ray, see [`TileRaycaster::crossing`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crossing<T = TilePosition> {
+    pub valid: Option<T>,
+    pub invalid: Option<T>,
 }
 
-pub struct TileRaycaster {
+/// Last valid and first invalid position along each ray of a beam, see
+/// [`TileRaycaster::beam_crossing`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BeamCrossing {
+    /// Crossing of each ray, indexed like the [`BeamIntersect`]s of the beam.
+    pub crossings: Vec<Crossing>,
+    /// Closest first invalid position of all rays, i.e. where the beam makes contact first.
+    pub nearest: Option<BeamHit>,
+}
+
+impl<T> Default for Crossing<T> {
+    fn default() -> Self {
+        Self {
+            valid: None,
+            invalid: None,
+        }
+    }
+}
+
+pub struct TileRaycaster<M = ()> {
     grid: Grid,
+    map: M,
 }
 
 impl TileRaycaster {
     #[must_use]
     pub const fn new(grid: Grid) -> Self {
-        Self { grid }
+        Self { grid, map: () }
+    }
+}
+
+/// Variants of the casting methods which consult the map instead of taking a predicate.
+/// Each one is named after the method it wraps with a `_blocked` suffix and returns the same
+/// errors.
+impl<M: TileMap> TileRaycaster<M> {
+    /// Creates a raycaster that answers blocking queries by consulting the provided `map`.
+    #[must_use]
+    pub const fn with_map(grid: Grid, map: M) -> Self {
+        Self { grid, map }
+    }
+
+    pub const fn map(&self) -> &M {
+        &self.map
+    }
+
+    pub fn map_mut(&mut self) -> &mut M {
+        &mut self.map
+    }
+
+    /// Like [`TileRaycaster::last_valid`] treating every tile that isn't blocking in the map as
+    /// valid.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::last_valid`].
+    pub fn last_valid_blocked<T: Into<Direction>>(
+        &self,
+        tp: &TilePosition,
+        direction: T,
+    ) -> CrissCrossResult<Option<TilePosition>> {
+        self.last_valid(tp, direction, |tp| !self.map.is_blocking(tp))
+    }
+
+    /// Returns the first tile along the ray that is blocking in the map.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::first_invalid`].
+    pub fn first_invalid_blocked<T: Into<Direction>>(
+        &self,
+        tp: &TilePosition,
+        direction: T,
+    ) -> CrissCrossResult<Option<TilePosition>> {
+        self.first_invalid(tp, direction, |tp| !self.map.is_blocking(tp))
+    }
+
+    /// Like [`TileRaycaster::beam_last_valid`] treating every tile that isn't blocking in the map
+    /// as valid.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::beam_last_valid`].
+    pub fn beam_last_valid_blocked<T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+    ) -> CrissCrossResult<Option<BeamIntersect>> {
+        self.beam_last_valid(
+            beam_center,
+            beam_width,
+            direction,
+            |BeamIntersect(_, tp)| !self.map.is_blocking(tp),
+        )
+    }
+
+    /// Like [`TileRaycaster::beam_crossing`] treating every tile that isn't blocking in the map
+    /// as valid.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::beam_crossing`].
+    pub fn beam_crossing_blocked<T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+    ) -> CrissCrossResult<BeamCrossing> {
+        self.beam_crossing(
+            beam_center,
+            beam_width,
+            direction,
+            |BeamIntersect(_, tp)| !self.map.is_blocking(tp),
+        )
+    }
+
+    /// Like [`TileRaycaster::cone_last_valid`] treating every tile that isn't blocking in the map
+    /// as valid.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cone_last_valid`].
+    pub fn cone_last_valid_blocked<T: Into<Direction>, A: Into<AngleRad>>(
+        &self,
+        origin: &TilePosition,
+        direction: T,
+        half_angle: A,
+    ) -> CrissCrossResult<Option<BeamIntersect>> {
+        self.cone_last_valid(origin, direction, half_angle, |BeamIntersect(_, tp)| {
+            !self.map.is_blocking(tp)
+        })
+    }
+
+    /// Like [`TileRaycaster::crossing`] treating every tile that isn't blocking in the map as
+    /// valid.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::crossing`].
+    pub fn crossing_blocked<T: Into<Direction>>(
+        &self,
+        tp: &TilePosition,
+        direction: T,
+    ) -> CrissCrossResult<Crossing> {
+        self.crossing(tp, direction, |tp| !self.map.is_blocking(tp))
+    }
+
+    /// Like [`TileRaycaster::has_line_of_sight`] using the map to determine blocking tiles.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::has_line_of_sight`].
+    pub fn has_line_of_sight_blocked(
+        &self,
+        from: &TilePosition,
+        to: &TilePosition,
+    ) -> CrissCrossResult<bool> {
+        self.has_line_of_sight(from, to, |tp| self.map.is_blocking(tp))
+    }
+
+    /// Like [`TileRaycaster::cast_columns`] using the map to determine walls.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_columns`].
+    pub fn cast_columns_blocked(
+        &self,
+        camera: &Camera,
+        screen_width: u32,
+    ) -> CrissCrossResult<Vec<Column>> {
+        self.cast_columns(camera, screen_width, |tp| self.map.is_blocking(tp))
+    }
+
+    /// Like [`TileRaycaster::field_of_view`] using the map to determine blocking tiles.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::field_of_view`].
+    pub fn field_of_view_blocked(
+        &self,
+        origin: &TilePosition,
+        radius: f32,
+    ) -> CrissCrossResult<BitGrid> {
+        self.field_of_view_with_blocked(origin, radius, FovAlgorithm::default())
+    }
+
+    /// Like [`TileRaycaster::field_of_view_with`] using the map to determine blocking tiles.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::field_of_view_with`].
+    pub fn field_of_view_with_blocked(
+        &self,
+        origin: &TilePosition,
+        radius: f32,
+        algorithm: FovAlgorithm,
+    ) -> CrissCrossResult<BitGrid> {
+        self.field_of_view_with(origin, radius, algorithm, |tp| self.map.is_blocking(tp))
     }
+}
 
+/// Rays are cast along a [`Direction`], i.e. either an angle in radians or a direction vector
+/// like `(dx, dy)`.
+/// All methods taking a [`TilePosition`], direction, beam width or cone half-angle return an
+/// error if the position lies outside of the grid, the direction isn't a finite, non-zero
+/// vector, the width isn't positive or the half-angle isn't between `0` and `PI` respectively.
+/// Methods casting rays without a maximum distance return [`CrissCrossError::UnboundedRay`] for
+/// wrapping grids, see [`Grid::with_wrapping`].
+impl<M> TileRaycaster<M> {
     pub const fn grid(&self) -> &Grid {
         &self.grid
     }
 
-    #[must_use]
-    pub fn cast_ray<T: Into<AngleRad>>(&self, tp: &TilePosition, angle: T) -> RayIter {
-        let intersections = Ray::new(self.grid.clone(), tp.clone(), angle);
-        intersections.into_iter()
+    /// Yields the positions at which the ray enters each tile it crosses until it leaves the grid.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::TileOutOfBounds`] if `tp` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::UnboundedRay`] if the grid is wrapping.
+    pub fn cast_ray<T: Into<Direction>>(
+        &self,
+        tp: &TilePosition,
+        direction: T,
+    ) -> CrissCrossResult<RayIter> {
+        self.validate_bounded()?;
+        Ok(self.ray(tp, direction)?.into_iter())
+    }
+
+    /// Like [`TileRaycaster::cast_ray`], but stops once the ray traveled `max_distance` in world
+    /// units.
+    /// The point at exactly that distance is emitted as the last intersection, unless the ray
+    /// leaves the grid before reaching it.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::TileOutOfBounds`] if `tp` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::InvalidDistance`] if `max_distance` is negative or NaN.
+    /// - [`CrissCrossError::UnboundedRay`] if `max_distance` is infinite and the grid is wrapping.
+    pub fn cast_ray_ranged<T: Into<Direction>>(
+        &self,
+        tp: &TilePosition,
+        direction: T,
+        max_distance: f32,
+    ) -> CrissCrossResult<RayIter> {
+        let ray = self.ray(tp, direction)?;
+        Ok(ray.ranged(self.validated_range(max_distance)?).into_iter())
+    }
+
+    /// Casts a ray from `from` towards `to` yielding the same intersections as
+    /// [`TileRaycaster::cast_ray`] and finally `to` itself.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::TileOutOfBounds`] if `from` lies outside of the grid.
+    /// - [`CrissCrossError::TileOutOfBounds`] if `to` lies outside of the grid.
+    pub fn cast_segment(
+        &self,
+        from: &TilePosition,
+        to: &TilePosition,
+    ) -> CrissCrossResult<RayIter> {
+        self.validate_tile_position(from)?;
+        self.validate_tile_position(to)?;
+        Ok(Ray::segment(self.grid.clone(), from.clone(), to.clone()).into_iter())
+    }
+
+    /// Like [`TileRaycaster::cast_ray`] but starts at and yields [`WorldCoords`].
+    /// If `from` lies outside of the grid, the point at which the ray enters it is yielded
+    /// first. Yields nothing if the ray misses the grid.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::NonFiniteCoordinates`] if `from` isn't finite.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::UnboundedRay`] if the grid is wrapping.
+    pub fn cast_ray_coords<T: Into<Direction>>(
+        &self,
+        from: &WorldCoords,
+        direction: T,
+    ) -> CrissCrossResult<impl Iterator<Item = WorldCoords>> {
+        self.validate_bounded()?;
+        validate_finite(from.x(), from.y())?;
+        let direction = validated_direction(direction)?;
+        let ray = Ray::from_world(self.grid.clone(), &self.on_grid(from), direction);
+        Ok(self.coords(ray))
+    }
+
+    /// Like [`TileRaycaster::cast_segment`] but starts at and yields [`WorldCoords`].
+    /// If `from` lies outside of the grid, the point at which the segment enters it is yielded
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::NonFiniteCoordinates`] if `from` or `to` isn't finite.
+    /// - [`CrissCrossError::OutOfBounds`] if `to` lies outside of the grid.
+    pub fn cast_segment_coords(
+        &self,
+        from: &WorldCoords,
+        to: &WorldCoords,
+    ) -> CrissCrossResult<impl Iterator<Item = WorldCoords>> {
+        validate_finite(from.x(), from.y())?;
+        validate_finite(to.x(), to.y())?;
+        let to = self.on_grid(to).to_tile_position_in(&self.grid)?;
+        let segment = Ray::segment_from_world(self.grid.clone(), &self.on_grid(from), to);
+        Ok(self.coords(segment))
+    }
+
+    /// Like [`TileRaycaster::cast_ray`] but takes and yields absolute world coordinates, see
+    /// [`Grid::with_origin`].
+    /// If `from` lies outside of the grid, the point at which the ray enters it is yielded
+    /// first. Yields nothing if the ray misses the grid.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::NonFiniteCoordinates`] if `from` isn't finite.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::UnboundedRay`] if the grid is wrapping.
+    pub fn cast_ray_world<T: Into<Direction>>(
+        &self,
+        from: (f32, f32),
+        direction: T,
+    ) -> CrissCrossResult<impl Iterator<Item = (f32, f32)>> {
+        validate_finite(from.0, from.1)?;
+        let (origin_x, origin_y) = (self.grid.origin_x, self.grid.origin_y);
+        Ok(self
+            .cast_ray_coords(&self.local(from), direction)?
+            .map(move |wc| (wc.x() + origin_x, wc.y() + origin_y)))
+    }
+
+    /// Like [`TileRaycaster::cast_segment`] but takes and yields absolute world coordinates,
+    /// see [`Grid::with_origin`].
+    /// If `from` lies outside of the grid, the point at which the segment enters it is yielded
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::NonFiniteCoordinates`] if `from` or `to` isn't finite.
+    /// - [`CrissCrossError::OutOfBounds`] if `to` lies outside of the grid.
+    pub fn cast_segment_world(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+    ) -> CrissCrossResult<impl Iterator<Item = (f32, f32)>> {
+        validate_finite(from.0, from.1)?;
+        validate_finite(to.0, to.1)?;
+        let (origin_x, origin_y) = (self.grid.origin_x, self.grid.origin_y);
+        Ok(self
+            .cast_segment_coords(&self.local(from), &self.local(to))?
+            .map(move |wc| (wc.x() + origin_x, wc.y() + origin_y)))
     }
 
-    pub fn cast_beam<T: Into<AngleRad>>(
+    /// Returns `true` if none of the tiles between `from` and `to` is blocking.
+    /// The tiles of `from` and `to` themselves are not checked, i.e. a wall can be seen.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_segment`].
+    pub fn has_line_of_sight<P>(
+        &self,
+        from: &TilePosition,
+        to: &TilePosition,
+        mut is_blocking: P,
+    ) -> CrissCrossResult<bool>
+    where
+        P: FnMut(&TilePosition) -> bool,
+    {
+        Ok(self
+            .cast_segment(from, to)?
+            .all(|tp| tp.is_same_tile(to) || !is_blocking(&tp)))
+    }
+
+    /// Yields the tiles crossed by rays spread evenly across the `beam_width`, together with the
+    /// index of the ray that crossed them.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::TileOutOfBounds`] if `beam_center` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::InvalidWidth`] if `beam_width` isn't positive and finite.
+    /// - [`CrissCrossError::UnboundedRay`] if the grid is wrapping.
+    pub fn cast_beam<T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+    ) -> CrissCrossResult<BeamIter> {
+        self.validate_bounded()?;
+        let rays = self.beam_rays(beam_center, beam_width, direction)?;
+        Ok(Beam::new(rays).into_iter())
+    }
+
+    /// Like [`TileRaycaster::cast_beam`], but each ray of the beam stops once it traveled
+    /// `max_distance` in world units, see [`TileRaycaster::cast_ray_ranged`].
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::TileOutOfBounds`] if `beam_center` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::InvalidWidth`] if `beam_width` isn't positive and finite.
+    /// - [`CrissCrossError::InvalidDistance`] if `max_distance` is negative or NaN.
+    /// - [`CrissCrossError::UnboundedRay`] if `max_distance` is infinite and the grid is wrapping.
+    pub fn cast_beam_ranged<T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+        max_distance: f32,
+    ) -> CrissCrossResult<BeamIter> {
+        let max_distance = self.validated_range(max_distance)?;
+        let rays = self
+            .beam_rays(beam_center, beam_width, direction)?
+            .into_iter()
+            .map(|ray| ray.ranged(max_distance))
+            .collect();
+        Ok(Beam::new(rays).into_iter())
+    }
+
+    /// Describes the rays cast for the beam, i.e. where each of them originates and in which
+    /// direction it is cast.
+    /// Use it to map the index of a [`BeamIntersect`] back to a position across the beam.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::TileOutOfBounds`] if `beam_center` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::InvalidWidth`] if `beam_width` isn't positive and finite.
+    pub fn beam_layout<T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+    ) -> CrissCrossResult<BeamLayout> {
+        let direction = self.validated_beam(beam_center, beam_width, direction)?;
+        Ok(beam_layout_from(
+            beam_center,
+            &self.grid,
+            beam_width,
+            &direction,
+        ))
+    }
+
+    /// Yields every tile overlapped by the rectangle the beam sweeps across the grid, ordered by
+    /// the distance at which the front of the beam first touches it.
+    /// Unlike [`TileRaycaster::cast_beam`] the beam isn't approximated by rays, thus no tiles
+    /// between them are skipped.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_beam`].
+    pub fn cast_beam_swept<T: Into<Direction>>(
         &self,
         beam_center: &TilePosition,
         beam_width: f32,
-        angle: T,
-    ) -> BeamIter {
-        let rays = rays_from(beam_center, &self.grid, beam_width, &angle.into());
-        Beam::new(self.grid.tile_size, rays).into_iter()
+        direction: T,
+    ) -> CrissCrossResult<SweptBeamIter> {
+        self.validate_bounded()?;
+        let direction = self.validated_beam(beam_center, beam_width, direction)?;
+        Ok(SweptBeamIter::new(swept_tiles(
+            &self.grid,
+            beam_center,
+            beam_width,
+            &direction,
+            None,
+        )))
     }
 
-    pub fn last_valid<P, T: Into<AngleRad>>(
+    /// Like [`TileRaycaster::cast_beam_swept`], but the beam stops once it traveled
+    /// `max_distance` in world units.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_beam_ranged`].
+    pub fn cast_beam_swept_ranged<T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+        max_distance: f32,
+    ) -> CrissCrossResult<SweptBeamIter> {
+        let direction = self.validated_beam(beam_center, beam_width, direction)?;
+        let max_distance = self.validated_range(max_distance)?;
+        Ok(SweptBeamIter::new(swept_tiles(
+            &self.grid,
+            beam_center,
+            beam_width,
+            &direction,
+            Some(max_distance),
+        )))
+    }
+
+    /// Yields the tiles inside the cone which widens by `half_angle` to either side of the
+    /// `direction` while spreading out from the `origin`, e.g. the light of a flashlight.
+    /// The cone is covered by rays fanned out across it, each tile is yielded once together with
+    /// the index of the ray that entered it first, counting from the counter-clockwise edge.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::TileOutOfBounds`] if `origin` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::InvalidHalfAngle`] if `half_angle` isn't between `0` and `PI`.
+    /// - [`CrissCrossError::UnboundedRay`] if the grid is wrapping.
+    pub fn cast_cone<T: Into<Direction>, A: Into<AngleRad>>(
+        &self,
+        origin: &TilePosition,
+        direction: T,
+        half_angle: A,
+    ) -> CrissCrossResult<BeamIter> {
+        self.validate_bounded()?;
+        let (direction, half_angle) = self.validated_cone(origin, direction, half_angle)?;
+        let rays = cone_rays_from(
+            origin,
+            &self.grid,
+            &direction,
+            &half_angle,
+            self.reach(origin),
+        );
+        Ok(Beam::new(rays).with_unique_tiles(&self.grid).into_iter())
+    }
+
+    /// Like [`TileRaycaster::cast_cone`], but each ray of the cone stops once it traveled
+    /// `max_distance` in world units, see [`TileRaycaster::cast_ray_ranged`].
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::TileOutOfBounds`] if `origin` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::InvalidHalfAngle`] if `half_angle` isn't between `0` and `PI`.
+    /// - [`CrissCrossError::InvalidDistance`] if `max_distance` is negative or NaN.
+    /// - [`CrissCrossError::UnboundedRay`] if `max_distance` is infinite and the grid is wrapping.
+    pub fn cast_cone_ranged<T: Into<Direction>, A: Into<AngleRad>>(
+        &self,
+        origin: &TilePosition,
+        direction: T,
+        half_angle: A,
+        max_distance: f32,
+    ) -> CrissCrossResult<BeamIter> {
+        let (direction, half_angle) = self.validated_cone(origin, direction, half_angle)?;
+        let max_distance = self.validated_range(max_distance)?;
+        let reach = max_distance.min(self.reach(origin));
+        let rays = cone_rays_from(origin, &self.grid, &direction, &half_angle, reach)
+            .into_iter()
+            .map(|ray| ray.ranged(max_distance))
+            .collect();
+        Ok(Beam::new(rays).with_unique_tiles(&self.grid).into_iter())
+    }
+
+    /// Returns the last tile along the ray before the first one for which `is_valid` returns
+    /// `false`.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_ray`].
+    pub fn last_valid<P, T: Into<Direction>>(
         &self,
         tp: &TilePosition,
-        angle: T,
+        direction: T,
         is_valid: P,
-    ) -> Option<TilePosition>
+    ) -> CrissCrossResult<Option<TilePosition>>
     where
         P: FnMut(&TilePosition) -> bool,
     {
-        self.cast_ray(tp, angle).take_while(is_valid).last()
+        Ok(self.cast_ray(tp, direction)?.take_while(is_valid).last())
     }
 
-    pub fn first_invalid<P, T: Into<AngleRad>>(
+    /// Returns the first tile along the ray for which `is_valid` returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_ray`].
+    pub fn first_invalid<P, T: Into<Direction>>(
         &self,
         tp: &TilePosition,
-        angle: T,
+        direction: T,
         is_valid: P,
-    ) -> Option<TilePosition>
+    ) -> CrissCrossResult<Option<TilePosition>>
     where
         P: FnMut(&TilePosition) -> bool,
     {
-        let mut iter = self.cast_ray(tp, angle).skip_while(is_valid);
-        iter.next()
+        let mut iter = self.cast_ray(tp, direction)?.skip_while(is_valid);
+        Ok(iter.next())
     }
 
-    pub fn beam_last_valid<P, T: Into<AngleRad>>(
+    /// Returns the last intersection of the beam before the first one for which `is_valid`
+    /// returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_beam`].
+    pub fn beam_last_valid<P, T: Into<Direction>>(
         &self,
         beam_center: &TilePosition,
         beam_width: f32,
-        angle: T,
+        direction: T,
         is_valid: P,
-    ) -> Option<BeamIntersect>
+    ) -> CrissCrossResult<Option<BeamIntersect>>
     where
         P: FnMut(&BeamIntersect) -> bool,
     {
-        self.cast_beam(beam_center, beam_width, angle)
+        Ok(self
+            .cast_beam(beam_center, beam_width, direction)?
             .take_while(is_valid)
-            .last()
+            .last())
+    }
+
+    /// Returns the last intersection of the cone before the first one for which `is_valid`
+    /// returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_cone`].
+    pub fn cone_last_valid<P, T: Into<Direction>, A: Into<AngleRad>>(
+        &self,
+        origin: &TilePosition,
+        direction: T,
+        half_angle: A,
+        is_valid: P,
+    ) -> CrissCrossResult<Option<BeamIntersect>>
+    where
+        P: FnMut(&BeamIntersect) -> bool,
+    {
+        Ok(self
+            .cast_cone(origin, direction, half_angle)?
+            .take_while(is_valid)
+            .last())
+    }
+
+    /// Like [`TileRaycaster::cast_beam`], but each ray stops on its own before the first tile for
+    /// which `is_valid` returns `false` while the remaining rays keep going.
+    /// Unlike [`TileRaycaster::beam_last_valid`] a single invalid tile thus doesn't stop the
+    /// entire beam.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_beam`].
+    pub fn cast_beam_occluded<P, T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+        is_valid: P,
+    ) -> CrissCrossResult<OccludedBeamIter<P>>
+    where
+        P: FnMut(&BeamIntersect) -> bool,
+    {
+        Ok(self
+            .cast_beam(beam_center, beam_width, direction)?
+            .occluded_by(is_valid))
     }
 
-    pub fn crossing<P, T: Into<AngleRad>>(
+    /// Last valid and first invalid position along each ray of the beam together with the
+    /// closest invalid position of all of them, see [`TileRaycaster::cast_beam_occluded`].
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_beam`].
+    pub fn beam_crossing<P, T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+        is_valid: P,
+    ) -> CrissCrossResult<BeamCrossing>
+    where
+        P: FnMut(&BeamIntersect) -> bool,
+    {
+        Ok(self
+            .cast_beam_occluded(beam_center, beam_width, direction, is_valid)?
+            .into_beam_crossing())
+    }
+
+    /// Computes the set of tiles visible from the `origin` within the given `radius` (in world
+    /// units).
+    /// Tiles for which `is_blocking` returns `true` are visible themselves, but stop rays from
+    /// reaching the tiles behind them.
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::field_of_view_with`].
+    pub fn field_of_view<P>(
+        &self,
+        origin: &TilePosition,
+        radius: f32,
+        is_blocking: P,
+    ) -> CrissCrossResult<BitGrid>
+    where
+        P: FnMut(&TilePosition) -> bool,
+    {
+        self.field_of_view_with(origin, radius, FovAlgorithm::default(), is_blocking)
+    }
+
+    /// Like [`TileRaycaster::field_of_view`] using the provided algorithm.
+    /// Use [`FovAlgorithm::Shadowcasting`] when visibility needs to be symmetric, i.e. if A
+    /// sees B then B sees A.
+    ///
+    /// # Errors
+    ///
+    /// [`CrissCrossError::TileOutOfBounds`] if `origin` lies outside of the grid.
+    pub fn field_of_view_with<P>(
+        &self,
+        origin: &TilePosition,
+        radius: f32,
+        algorithm: FovAlgorithm,
+        is_blocking: P,
+    ) -> CrissCrossResult<BitGrid>
+    where
+        P: FnMut(&TilePosition) -> bool,
+    {
+        self.validate_tile_position(origin)?;
+        Ok(field_of_view(
+            &self.grid,
+            origin,
+            radius,
+            algorithm,
+            is_blocking,
+        ))
+    }
+
+    /// Casts one ray per screen column from the camera and reports the first tile hit for
+    /// which `is_blocking` returns `true`, including the data needed to render it in a
+    /// first-person view.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::UnboundedRay`] if the grid is wrapping.
+    /// - [`CrissCrossError::TileOutOfBounds`] if the camera is positioned outside of the grid.
+    /// - [`CrissCrossError::NonFiniteAngle`] if the heading or field of view of the camera isn't finite.
+    pub fn cast_columns<P>(
+        &self,
+        camera: &Camera,
+        screen_width: u32,
+        is_blocking: P,
+    ) -> CrissCrossResult<Vec<Column>>
+    where
+        P: FnMut(&TilePosition) -> bool,
+    {
+        self.validate_bounded()?;
+        self.validate_tile_position(&camera.position)?;
+        validated_angle(camera.heading.clone())?;
+        validated_angle(camera.fov.clone())?;
+        Ok(cast_columns(&self.grid, camera, screen_width, is_blocking))
+    }
+
+    /// Last valid and first invalid position along the ray, see [`Crossing`].
+    ///
+    /// # Errors
+    ///
+    /// See [`TileRaycaster::cast_ray`].
+    pub fn crossing<P, T: Into<Direction>>(
         &self,
         tp: &TilePosition,
-        angle: T,
-        mut is_valid: P,
-    ) -> Crossing
+        direction: T,
+        is_valid: P,
+    ) -> CrissCrossResult<Crossing>
     where
         P: FnMut(&TilePosition) -> bool,
     {
-        let mut iter = self.cast_ray(tp, angle).peekable();
-        let mut previous = iter.next();
-
-        match previous {
-            None => Crossing::default(),
-            Some(prev) if !is_valid(&prev) => Crossing::default(),
-            Some(_) => {
-                let (valid, invalid) = loop {
-                    let next = iter.next();
-                    match (previous, next) {
-                        (Some(prev), Some(next)) => {
-                            if is_valid(&next) {
-                                previous = Some(next);
-                                continue;
-                            }
-                            break (Some(prev), Some(next));
-                        }
-                        (Some(prev), None) => {
-                            if is_valid(&prev) {
-                                break (Some(prev), None);
-                            }
-                            break (None, None);
+        Ok(crossing_of(self.cast_ray(tp, direction)?, is_valid))
+    }
+}
+
+/// Splits the positions yielded by `iter` at the first one for which `is_valid` returns `false`.
+pub fn crossing_of<T, I, P>(iter: I, mut is_valid: P) -> Crossing<T>
+where
+    I: Iterator<Item = T>,
+    P: FnMut(&T) -> bool,
+{
+    let mut iter = iter.peekable();
+    let mut previous = iter.next();
+
+    match previous {
+        None => Crossing::default(),
+        Some(prev) if !is_valid(&prev) => Crossing::default(),
+        Some(_) => {
+            let (valid, invalid) = loop {
+                let next = iter.next();
+                match (previous, next) {
+                    (Some(prev), Some(next)) => {
+                        if is_valid(&next) {
+                            previous = Some(next);
+                            continue;
                         }
-                        (None, None) => break (None, None),
-                        #[allow(clippy::panic)]
-                        (None, Some(_)) => {
-                            panic!("(prev: None, next: Some(_)) should be impossible")
+                        break (Some(prev), Some(next));
+                    }
+                    (Some(prev), None) => {
+                        if is_valid(&prev) {
+                            break (Some(prev), None);
                         }
+                        break (None, None);
+                    }
+                    (None, None) => break (None, None),
+                    #[allow(clippy::panic)]
+                    (None, Some(_)) => {
+                        panic!("(prev: None, next: Some(_)) should be impossible")
                     }
-                };
-                Crossing { valid, invalid }
-            }
+                }
+            };
+            Crossing { valid, invalid }
+        }
+    }
+}
+
+//
+// Validation
+//
+impl<M> TileRaycaster<M> {
+    fn validate_tile_position(&self, tp: &TilePosition) -> CrissCrossResult<()> {
+        if self.grid.contains(tp) {
+            Ok(())
+        } else {
+            Err(CrissCrossError::TileOutOfBounds { x: tp.x, y: tp.y })
+        }
+    }
+
+    /// Rays that aren't ranged never end on a wrapping grid.
+    const fn validate_bounded(&self) -> CrissCrossResult<()> {
+        if self.grid.wrapping {
+            Err(CrissCrossError::UnboundedRay)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ranged rays only end on a wrapping grid if their maximum distance is finite.
+    fn validated_range(&self, max_distance: f32) -> CrissCrossResult<f32> {
+        let max_distance = validated_distance(max_distance)?;
+        if max_distance.is_infinite() {
+            self.validate_bounded()?;
+        }
+        Ok(max_distance)
+    }
+
+    fn ray<T: Into<Direction>>(&self, tp: &TilePosition, direction: T) -> CrissCrossResult<Ray> {
+        self.validate_tile_position(tp)?;
+        let direction = validated_direction(direction)?;
+        Ok(Ray::new(self.grid.clone(), tp.clone(), direction))
+    }
+
+    fn beam_rays<T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+    ) -> CrissCrossResult<Vec<Ray>> {
+        let direction = self.validated_beam(beam_center, beam_width, direction)?;
+        Ok(rays_from(beam_center, &self.grid, beam_width, &direction))
+    }
+
+    fn validated_beam<T: Into<Direction>>(
+        &self,
+        beam_center: &TilePosition,
+        beam_width: f32,
+        direction: T,
+    ) -> CrissCrossResult<Direction> {
+        self.validate_tile_position(beam_center)?;
+        let direction = validated_direction(direction)?;
+        if beam_width.is_finite() && beam_width > 0.0 {
+            Ok(direction)
+        } else {
+            Err(CrissCrossError::InvalidWidth(beam_width))
         }
     }
+
+    fn validated_cone<T: Into<Direction>, A: Into<AngleRad>>(
+        &self,
+        origin: &TilePosition,
+        direction: T,
+        half_angle: A,
+    ) -> CrissCrossResult<(Direction, AngleRad)> {
+        self.validate_tile_position(origin)?;
+        Ok((
+            validated_direction(direction)?,
+            validated_half_angle(half_angle)?,
+        ))
+    }
+
+    /// Distance from the `origin` to the most distant corner of the grid.
+    fn reach(&self, origin: &TilePosition) -> f32 {
+        let wc = WorldCoords::from_tile_position(origin, self.grid.tile_size);
+        #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+        let (width, height) = (self.grid.width as f32, self.grid.height as f32);
+        wc.x()
+            .max(width - wc.x())
+            .hypot(wc.y().max(height - wc.y()))
+    }
+
+    fn local(&self, (x, y): (f32, f32)) -> WorldCoords {
+        WorldCoords::new(
+            x - self.grid.origin_x,
+            y - self.grid.origin_y,
+            self.grid.tile_size,
+        )
+    }
+
+    /// Ensures that the coordinates are based on the tile size of the grid.
+    fn on_grid(&self, wc: &WorldCoords) -> WorldCoords {
+        WorldCoords::new(wc.x(), wc.y(), self.grid.tile_size)
+    }
+
+    fn coords(&self, ray: Option<Ray>) -> impl Iterator<Item = WorldCoords> {
+        let tile_size = self.grid.tile_size;
+        ray.into_iter()
+            .flat_map(Ray::into_iter)
+            .map(move |tp| WorldCoords::from_tile_position(&tp, tile_size))
+    }
+}
+
+fn validate_finite(x: f32, y: f32) -> CrissCrossResult<()> {
+    if x.is_finite() && y.is_finite() {
+        Ok(())
+    } else {
+        Err(CrissCrossError::NonFiniteCoordinates { x, y })
+    }
 }
@@ -0,0 +1,284 @@
+use crate::{
+    angle::{validated_direction, Direction, DirectionX, DirectionY},
+    position::SignedTilePosition,
+    ray_range::RayRange,
+    util::{validated_distance, CORNER_TOLERANCE},
+    CrissCrossResult, TileSize,
+};
+
+/// Casts rays across an unbounded tile space, i.e. for worlds that don't have a fixed number of
+/// `cols` and `rows`.
+///
+/// Since such rays never leave the world they need to be stopped by the caller, either by
+/// limiting them to a maximum distance or by only consuming them until a predicate holds, see
+/// [`UnboundedRaycaster::first_blocking`].
+#[derive(Debug, Clone)]
+pub struct UnboundedRaycaster {
+    tile_size: TileSize,
+}
+
+impl UnboundedRaycaster {
+    /// # Errors
+    ///
+    /// [`crate::CrissCrossError::InvalidTileSize`] unless tile width and height are positive
+    /// and finite.
+    pub fn new<T: Into<TileSize>>(tile_size: T) -> CrissCrossResult<Self> {
+        Ok(Self {
+            tile_size: tile_size.into().validated()?,
+        })
+    }
+
+    pub const fn tile_size(&self) -> TileSize {
+        self.tile_size
+    }
+
+    /// Yields the positions at which the ray cast along the `direction`, i.e. an angle in
+    /// radians or a vector like `(dx, dy)`, enters each tile it crosses.
+    /// The returned iterator never ends, thus needs to be limited, e.g. via
+    /// [`Iterator::take_while`].
+    ///
+    /// # Errors
+    ///
+    /// [`crate::CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero
+    /// vector.
+    pub fn cast_ray<T: Into<Direction>>(
+        &self,
+        origin: &SignedTilePosition,
+        direction: T,
+    ) -> CrissCrossResult<SignedRayIter> {
+        let direction = validated_direction(direction)?;
+        Ok(SignedRayIter::new(self.tile_size, origin, &direction))
+    }
+
+    /// Like [`UnboundedRaycaster::cast_ray`], but stops once the ray traveled `max_distance`
+    /// in world units.
+    /// The point at exactly that distance is emitted as the last intersection.
+    ///
+    /// # Errors
+    ///
+    /// - [`crate::CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero
+    ///   vector.
+    /// - [`crate::CrissCrossError::InvalidDistance`] if `max_distance` is negative or NaN.
+    pub fn cast_ray_ranged<T: Into<Direction>>(
+        &self,
+        origin: &SignedTilePosition,
+        direction: T,
+        max_distance: f32,
+    ) -> CrissCrossResult<SignedRayIter> {
+        let mut iter = self.cast_ray(origin, direction)?;
+        iter.range = RayRange::new(validated_distance(max_distance)?);
+        Ok(iter)
+    }
+
+    /// Returns the first tile within `max_distance` along the ray for which `is_blocking`
+    /// returns `true`.
+    ///
+    /// # Errors
+    ///
+    /// See [`UnboundedRaycaster::cast_ray_ranged`].
+    pub fn first_blocking<P, T: Into<Direction>>(
+        &self,
+        origin: &SignedTilePosition,
+        direction: T,
+        max_distance: f32,
+        mut is_blocking: P,
+    ) -> CrissCrossResult<Option<SignedTilePosition>>
+    where
+        P: FnMut(&SignedTilePosition) -> bool,
+    {
+        Ok(self
+            .cast_ray_ranged(origin, direction, max_distance)?
+            .find(|stp| is_blocking(stp)))
+    }
+}
+
+/// Iterates over the positions at which a ray enters each tile of an unbounded tile space.
+///
+/// Positions on the left or bottom edge of a tile entered while moving left or down are
+/// reported inside the previous tile, i.e. with `rel_x`/`rel_y` equal to the tile width/height.
+#[derive(Debug)]
+pub struct SignedRayIter {
+    tile_size: TileSize,
+    cos: f32,
+    sin: f32,
+    // Origin relative to the bottom left of its tile, all positions are computed relative to
+    // that tile to retain precision far away from (0, 0)
+    origin: (i64, i64),
+    rel_origin: (f32, f32),
+    tile: (i64, i64),
+    step: (i64, i64),
+    // Column/row of the next vertical/horizontal edge the ray crosses
+    next_edge: (i64, i64),
+    // Distance at which the ray crosses that vertical/horizontal edge
+    next_x: f32,
+    next_y: f32,
+    range: RayRange,
+}
+
+impl SignedRayIter {
+    #[allow(clippy::integer_arithmetic)]
+    fn new(tile_size: TileSize, origin: &SignedTilePosition, direction: &Direction) -> Self {
+        let origin = floored(
+            tile_size,
+            (origin.x, origin.y),
+            (origin.rel_x, origin.rel_y),
+        );
+        let step_x = match DirectionX::from(direction) {
+            DirectionX::Right => 1,
+            DirectionX::Left => -1,
+            DirectionX::Parallel => 0,
+        };
+        let step_y = match DirectionY::from(direction) {
+            DirectionY::Up => 1,
+            DirectionY::Down => -1,
+            DirectionY::Parallel => 0,
+        };
+        // Moving right/up the first edge is the one of the next tile, otherwise it's the one of
+        // the origin tile itself
+        let next_edge = (origin.x + step_x.max(0), origin.y + step_y.max(0));
+        let mut me = Self {
+            tile_size,
+            cos: direction.x(),
+            sin: direction.y(),
+            origin: (origin.x, origin.y),
+            rel_origin: (origin.rel_x, origin.rel_y),
+            tile: (origin.x, origin.y),
+            step: (step_x, step_y),
+            next_edge,
+            next_x: f32::INFINITY,
+            next_y: f32::INFINITY,
+            range: RayRange::default(),
+        };
+        me.next_x = me.col_distance(next_edge.0);
+        me.next_y = me.row_distance(next_edge.1);
+        me
+    }
+
+    /// Distance from the origin at which the ray crosses the left edge of `col`.
+    /// Computed from the origin rather than accumulated per tile, so it doesn't drift on long
+    /// rays.
+    #[allow(
+        clippy::integer_arithmetic,
+        clippy::as_conversions,
+        clippy::cast_precision_loss
+    )]
+    fn col_distance(&self, col: i64) -> f32 {
+        if self.step.0 == 0 {
+            return f32::INFINITY;
+        }
+        let cols = (col - self.origin.0) as f32;
+        cols.mul_add(self.tile_size.width, -self.rel_origin.0) / self.cos
+    }
+
+    /// Like [`SignedRayIter::col_distance`] for the bottom edge of `row`.
+    #[allow(
+        clippy::integer_arithmetic,
+        clippy::as_conversions,
+        clippy::cast_precision_loss
+    )]
+    fn row_distance(&self, row: i64) -> f32 {
+        if self.step.1 == 0 {
+            return f32::INFINITY;
+        }
+        let rows = (row - self.origin.1) as f32;
+        rows.mul_add(self.tile_size.height, -self.rel_origin.1) / self.sin
+    }
+
+    /// Position relative to the bottom left of the given tile at the given distance.
+    #[allow(
+        clippy::integer_arithmetic,
+        clippy::as_conversions,
+        clippy::cast_precision_loss
+    )]
+    fn rel_at(&self, (x, y): (i64, i64), distance: f32) -> (f32, f32) {
+        let (ox, oy) = self.rel_origin;
+        let tiles_x = (x - self.origin.0) as f32;
+        let tiles_y = (y - self.origin.1) as f32;
+        (
+            tiles_x.mul_add(-self.tile_size.width, self.cos.mul_add(distance, ox)),
+            tiles_y.mul_add(-self.tile_size.height, self.sin.mul_add(distance, oy)),
+        )
+    }
+
+    fn end_at(&self, distance: f32) -> SignedTilePosition {
+        floored(
+            self.tile_size,
+            self.origin,
+            self.rel_at(self.origin, distance),
+        )
+    }
+}
+
+/// Moves a position whose relative offset lies outside of its tile into the tile containing it.
+#[allow(
+    clippy::integer_arithmetic,
+    clippy::as_conversions,
+    clippy::cast_possible_truncation
+)]
+fn floored(
+    tile_size: TileSize,
+    (x, y): (i64, i64),
+    (rel_x, rel_y): (f32, f32),
+) -> SignedTilePosition {
+    let tiles_x = (rel_x / tile_size.width).floor();
+    let tiles_y = (rel_y / tile_size.height).floor();
+    SignedTilePosition::new(
+        x + tiles_x as i64,
+        y + tiles_y as i64,
+        tiles_x.mul_add(-tile_size.width, rel_x),
+        tiles_y.mul_add(-tile_size.height, rel_y),
+    )
+}
+
+impl Iterator for SignedRayIter {
+    type Item = SignedTilePosition;
+
+    #[allow(clippy::integer_arithmetic)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_ended() {
+            return None;
+        }
+        let distance = self.next_x.min(self.next_y);
+        if !self.range.within(distance) {
+            let max_distance = self.range.finish()?;
+            return Some(self.end_at(max_distance));
+        }
+
+        let corner = (self.next_x - self.next_y).abs() < CORNER_TOLERANCE;
+        let cross_x = corner || self.next_x < self.next_y;
+        let cross_y = corner || self.next_y < self.next_x;
+        if cross_x {
+            self.tile.0 += self.step.0;
+            self.next_edge.0 += self.step.0;
+            self.next_x = self.col_distance(self.next_edge.0);
+        }
+        if cross_y {
+            self.tile.1 += self.step.1;
+            self.next_edge.1 += self.step.1;
+            self.next_y = self.row_distance(self.next_edge.1);
+        }
+
+        let (mut rel_x, mut rel_y) = self.rel_at(self.tile, distance);
+        if cross_x {
+            rel_x = if self.step.0 < 0 {
+                self.tile_size.width
+            } else {
+                0.0
+            };
+        }
+        if cross_y {
+            rel_y = if self.step.1 < 0 {
+                self.tile_size.height
+            } else {
+                0.0
+            };
+        }
+        self.range.crossed(distance);
+        Some(SignedTilePosition::new(
+            self.tile.0,
+            self.tile.1,
+            rel_x,
+            rel_y,
+        ))
+    }
+}
@@ -4,9 +4,22 @@ use std::f32::{consts::TAU, EPSILON};
 // work around cargo bug
 use crate::{
     position::{SignedTilePosition, WorldCoords},
-    TilePosition,
+    CrissCrossError, CrissCrossResult, TilePosition,
 };
 
+// Crossings of boundaries closer together than this are considered to pass through the edge
+// or corner shared by them.
+pub const CORNER_TOLERANCE: f32 = 1e-5;
+
+/// Maximum distances of ranged rays may be infinite, but neither negative nor NaN.
+pub fn validated_distance(max_distance: f32) -> CrissCrossResult<f32> {
+    if max_distance >= 0.0 {
+        Ok(max_distance)
+    } else {
+        Err(CrissCrossError::InvalidDistance(max_distance))
+    }
+}
+
 pub fn floats_equal(f1: f32, f2: f32) -> bool {
     (f1 - f2).abs() < EPSILON
 }
@@ -56,14 +69,3 @@ pub fn round_stp(stp: &SignedTilePosition) -> SignedTilePosition {
         rel_y: round(*rel_y, 3),
     }
 }
-
-#[cfg(test)]
-pub fn round_ostp(tp: Option<SignedTilePosition>) -> Option<SignedTilePosition> {
-    let SignedTilePosition { x, y, rel_x, rel_y } = tp?;
-    Some(SignedTilePosition {
-        x,
-        y,
-        rel_x: round(rel_x, 3),
-        rel_y: round(rel_y, 3),
-    })
-}
@@ -0,0 +1,376 @@
+use std::convert::TryFrom;
+
+use crate::{
+    ray_range::RayRange,
+    tile_raycaster::{crossing_of, Crossing},
+    util::{floats_equal, validated_distance, CORNER_TOLERANCE},
+    CrissCrossError, CrissCrossResult,
+};
+
+/// Position inside a voxel given by the coordinates of the voxel and the offset from its
+/// bottom left front corner, i.e. the corner closest to the origin of the grid.
+#[derive(Debug, Clone)]
+pub struct VoxelPosition {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+    pub rel_x: f32,
+    pub rel_y: f32,
+    pub rel_z: f32,
+}
+
+impl PartialEq for VoxelPosition {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_same_voxel(other)
+            && floats_equal(self.rel_x, other.rel_x)
+            && floats_equal(self.rel_y, other.rel_y)
+            && floats_equal(self.rel_z, other.rel_z)
+    }
+}
+
+impl VoxelPosition {
+    #[must_use]
+    pub const fn new(x: u32, y: u32, z: u32, rel_x: f32, rel_y: f32, rel_z: f32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            rel_x,
+            rel_y,
+            rel_z,
+        }
+    }
+
+    pub const fn is_same_voxel(&self, other: &Self) -> bool {
+        self.x == other.x && self.y == other.y && self.z == other.z
+    }
+}
+
+/// Grid of `cols` x `rows` x `layers` cubes whose origin is located at the corner of the voxel
+/// at `(0, 0, 0)`.
+#[derive(Debug, Clone)]
+pub struct VoxelGrid {
+    pub cols: u32,
+    pub rows: u32,
+    pub layers: u32,
+    pub voxel_size: f32,
+}
+
+impl VoxelGrid {
+    /// # Errors
+    ///
+    /// [`CrissCrossError::InvalidTileSize`] unless the voxel size is positive and finite.
+    pub fn new(cols: u32, rows: u32, layers: u32, voxel_size: f32) -> CrissCrossResult<Self> {
+        if voxel_size.is_finite() && voxel_size > 0.0 {
+            Ok(Self {
+                cols,
+                rows,
+                layers,
+                voxel_size,
+            })
+        } else {
+            Err(CrissCrossError::InvalidTileSize {
+                width: voxel_size,
+                height: voxel_size,
+            })
+        }
+    }
+
+    /// Returns `true` if the voxel position lies inside the grid and its relative offset inside
+    /// its voxel.
+    pub fn contains(&self, vp: &VoxelPosition) -> bool {
+        let inside = |rel: f32| (0.0..=self.voxel_size).contains(&rel);
+        vp.x < self.cols
+            && vp.y < self.rows
+            && vp.z < self.layers
+            && inside(vp.rel_x)
+            && inside(vp.rel_y)
+            && inside(vp.rel_z)
+    }
+
+    const fn dimensions(&self) -> [u32; 3] {
+        [self.cols, self.rows, self.layers]
+    }
+}
+
+/// Casts rays through a [`VoxelGrid`] offering the same predicate driven helpers as
+/// [`crate::TileRaycaster`].
+///
+/// Uses the voxel traversal by Amanatides and Woo, see
+/// <http://www.cse.yorku.ca/~amana/research/grid.pdf>.
+pub struct VoxelRaycaster {
+    grid: VoxelGrid,
+}
+
+/// All methods taking a [`VoxelPosition`] or direction return an error if the position lies
+/// outside of the grid or the direction isn't a finite, non-zero vector respectively.
+impl VoxelRaycaster {
+    #[must_use]
+    pub const fn new(grid: VoxelGrid) -> Self {
+        Self { grid }
+    }
+
+    pub const fn grid(&self) -> &VoxelGrid {
+        &self.grid
+    }
+
+    /// Yields the positions at which the ray cast along the `direction` vector enters each
+    /// voxel it crosses until it leaves the grid.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::VoxelOutOfBounds`] if `vp` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    pub fn cast_ray(
+        &self,
+        vp: &VoxelPosition,
+        direction: (f32, f32, f32),
+    ) -> CrissCrossResult<VoxelRayIter> {
+        if !self.grid.contains(vp) {
+            return Err(CrissCrossError::VoxelOutOfBounds {
+                x: vp.x,
+                y: vp.y,
+                z: vp.z,
+            });
+        }
+        let (dx, dy, dz) = direction;
+        let length = dx.hypot(dy).hypot(dz);
+        if !(length.is_finite() && length > 0.0) {
+            return Err(CrissCrossError::InvalidDirection);
+        }
+        let direction = [dx / length, dy / length, dz / length];
+        Ok(VoxelRayIter::new(self.grid.clone(), vp, direction))
+    }
+
+    /// Like [`VoxelRaycaster::cast_ray`], but stops once the ray traveled `max_distance` in
+    /// world units.
+    /// The point at exactly that distance is emitted as the last intersection, unless the ray
+    /// leaves the grid before reaching it.
+    ///
+    /// # Errors
+    ///
+    /// - [`CrissCrossError::VoxelOutOfBounds`] if `vp` lies outside of the grid.
+    /// - [`CrissCrossError::InvalidDirection`] if the direction isn't a finite, non-zero vector.
+    /// - [`CrissCrossError::InvalidDistance`] if `max_distance` is negative or NaN.
+    pub fn cast_ray_ranged(
+        &self,
+        vp: &VoxelPosition,
+        direction: (f32, f32, f32),
+        max_distance: f32,
+    ) -> CrissCrossResult<VoxelRayIter> {
+        let mut iter = self.cast_ray(vp, direction)?;
+        iter.range = RayRange::new(validated_distance(max_distance)?);
+        Ok(iter)
+    }
+
+    /// Returns the last voxel along the ray before the first one for which `is_valid` returns
+    /// `false`.
+    ///
+    /// # Errors
+    ///
+    /// See [`VoxelRaycaster::cast_ray`].
+    pub fn last_valid<P>(
+        &self,
+        vp: &VoxelPosition,
+        direction: (f32, f32, f32),
+        is_valid: P,
+    ) -> CrissCrossResult<Option<VoxelPosition>>
+    where
+        P: FnMut(&VoxelPosition) -> bool,
+    {
+        Ok(self.cast_ray(vp, direction)?.take_while(is_valid).last())
+    }
+
+    /// Returns the first voxel along the ray for which `is_valid` returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// See [`VoxelRaycaster::cast_ray`].
+    pub fn first_invalid<P>(
+        &self,
+        vp: &VoxelPosition,
+        direction: (f32, f32, f32),
+        is_valid: P,
+    ) -> CrissCrossResult<Option<VoxelPosition>>
+    where
+        P: FnMut(&VoxelPosition) -> bool,
+    {
+        let mut iter = self.cast_ray(vp, direction)?.skip_while(is_valid);
+        Ok(iter.next())
+    }
+
+    /// Last valid and first invalid position along the ray, see [`Crossing`].
+    ///
+    /// # Errors
+    ///
+    /// See [`VoxelRaycaster::cast_ray`].
+    pub fn crossing<P>(
+        &self,
+        vp: &VoxelPosition,
+        direction: (f32, f32, f32),
+        is_valid: P,
+    ) -> CrissCrossResult<Crossing<VoxelPosition>>
+    where
+        P: FnMut(&VoxelPosition) -> bool,
+    {
+        Ok(crossing_of(self.cast_ray(vp, direction)?, is_valid))
+    }
+}
+
+/// Iterates over the positions at which a ray enters each voxel it crosses.
+///
+/// Positions on the lower face of a voxel entered while moving towards negative coordinates
+/// are reported inside the previous voxel, i.e. with the relative offset equal to the voxel
+/// size.
+#[derive(Debug)]
+pub struct VoxelRayIter {
+    grid: VoxelGrid,
+    // All per axis values are stored as [x, y, z]
+    // Origin relative to the corner of its voxel, all positions are computed relative to that
+    // voxel to retain precision for large voxel coordinates
+    origin: [i64; 3],
+    rel_origin: [f32; 3],
+    direction: [f32; 3],
+    voxel: [i64; 3],
+    step: [i64; 3],
+    // Distance at which the ray crosses the next boundary along each axis
+    next: [f32; 3],
+    // Distance travelled between crossing two boundaries along each axis
+    delta: [f32; 3],
+    range: RayRange,
+}
+
+impl VoxelRayIter {
+    fn new(grid: VoxelGrid, vp: &VoxelPosition, direction: [f32; 3]) -> Self {
+        let size = grid.voxel_size;
+        let [dx, dy, dz] = direction;
+        let axes = [
+            Axis::new(size, vp.rel_x, dx),
+            Axis::new(size, vp.rel_y, dy),
+            Axis::new(size, vp.rel_z, dz),
+        ];
+        let origin = [i64::from(vp.x), i64::from(vp.y), i64::from(vp.z)];
+        Self {
+            grid,
+            origin,
+            rel_origin: [vp.rel_x, vp.rel_y, vp.rel_z],
+            direction,
+            voxel: origin,
+            step: axes.map(|axis| axis.step),
+            next: axes.map(|axis| axis.next),
+            delta: axes.map(|axis| axis.delta),
+            range: RayRange::default(),
+        }
+    }
+
+    /// Offset of the point at the given distance from the corner of the current voxel.
+    #[allow(
+        clippy::integer_arithmetic,
+        clippy::cast_precision_loss,
+        clippy::as_conversions
+    )]
+    fn rel_at(&self, distance: f32) -> [f32; 3] {
+        let size = self.grid.voxel_size;
+        let mut rel = [0.0; 3];
+        for ((((rel, origin), rel_origin), direction), voxel) in rel
+            .iter_mut()
+            .zip(self.origin.iter())
+            .zip(self.rel_origin.iter())
+            .zip(self.direction.iter())
+            .zip(self.voxel.iter())
+        {
+            let voxels = (voxel - origin) as f32;
+            *rel = voxels.mul_add(-size, direction.mul_add(distance, *rel_origin));
+        }
+        rel
+    }
+
+    fn voxel_position(&self, [rel_x, rel_y, rel_z]: [f32; 3]) -> Option<VoxelPosition> {
+        let [x, y, z] = self.voxel;
+        Some(VoxelPosition::new(
+            u32::try_from(x).ok()?,
+            u32::try_from(y).ok()?,
+            u32::try_from(z).ok()?,
+            rel_x,
+            rel_y,
+            rel_z,
+        ))
+    }
+
+    fn inside_grid(&self) -> bool {
+        self.voxel
+            .iter()
+            .zip(self.grid.dimensions().iter())
+            .all(|(voxel, dim)| *voxel >= 0 && *voxel < i64::from(*dim))
+    }
+}
+
+/// Traversal parameters along a single axis.
+#[derive(Clone, Copy)]
+struct Axis {
+    step: i64,
+    next: f32,
+    delta: f32,
+}
+
+impl Axis {
+    fn new(size: f32, rel: f32, direction: f32) -> Self {
+        let (step, next, delta) = if direction > 0.0 {
+            (1, (size - rel) / direction, size / direction)
+        } else if direction < 0.0 {
+            (-1, rel / -direction, size / -direction)
+        } else {
+            // Never crosses a boundary along this axis
+            (0, f32::INFINITY, f32::INFINITY)
+        };
+        Self { step, next, delta }
+    }
+}
+
+impl Iterator for VoxelRayIter {
+    type Item = VoxelPosition;
+
+    #[allow(clippy::integer_arithmetic)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_ended() {
+            return None;
+        }
+        let distance = self.next.iter().copied().fold(f32::INFINITY, f32::min);
+        if !self.range.within(distance) {
+            let max_distance = self.range.finish()?;
+            return self.voxel_position(self.rel_at(max_distance));
+        }
+
+        // Step along all axes whose boundaries are crossed at this distance, i.e. pass through
+        // edges and corners instead of entering the voxels adjacent to them
+        let mut crossed = [false; 3];
+        for ((((crossed, next), delta), voxel), step) in crossed
+            .iter_mut()
+            .zip(self.next.iter_mut())
+            .zip(self.delta.iter())
+            .zip(self.voxel.iter_mut())
+            .zip(self.step.iter())
+        {
+            if *next - distance < CORNER_TOLERANCE {
+                *crossed = true;
+                *next += delta;
+                *voxel += step;
+            }
+        }
+        if !self.inside_grid() {
+            self.range.stop();
+            return None;
+        }
+        self.range.crossed(distance);
+
+        // Snap onto the boundaries that were crossed to avoid rounding errors
+        let size = self.grid.voxel_size;
+        let mut rel = self.rel_at(distance);
+        for ((rel, crossed), step) in rel.iter_mut().zip(crossed.iter()).zip(self.step.iter()) {
+            if *crossed {
+                *rel = if *step < 0 { size } else { 0.0 };
+            }
+        }
+        self.voxel_position(rel)
+    }
+}
@@ -1,11 +1,15 @@
 mod common;
-use common::round_beam_intersect;
-use crisscross::{BeamIntersect, Grid, TilePosition, TileRaycaster};
+use common::{round, round_beam_intersect, round_cutoff, round_tp};
+use crisscross::{
+    BeamCrossing, BeamHit, BeamIntersect, BeamRay, CrissCrossError, Crossing, Grid, SweptTile,
+    TilePosition, TileRaycaster,
+};
 
 fn cast(grid: &Grid, center: &TilePosition, width: f32, angle: f32) -> Vec<BeamIntersect> {
     let tc = TileRaycaster::new(grid.clone());
     let bis: Vec<BeamIntersect> = tc
         .cast_beam(&center, width, angle)
+        .unwrap()
         .map(round_beam_intersect)
         .collect();
 
@@ -20,7 +24,7 @@ fn cast(grid: &Grid, center: &TilePosition, width: f32, angle: f32) -> Vec<BeamI
 
 #[test]
 fn cast_beam_4x4grid() {
-    let grid = Grid::new(4, 4, 1.0);
+    let grid = Grid::new(4, 4, 1.0).unwrap();
     let center = TilePosition::from(((1, 0.5), (1, 0.5)));
     let width = 0.8;
     let angle = 0.0;
@@ -45,18 +49,20 @@ fn cast_beam_4x4grid() {
     let center = TilePosition::from(((0, 0.3), (2, 0.3)));
     let width = 2.2;
     let angle = 320_f32.to_radians();
+    // The two rays originating left of the grid enter it through its left edge
     assert_eq!(
         cast(&grid, &center, width, angle),
         [
-            BeamIntersect(0, ((0, 0.087), (1, 1.000)).into()),
-            BeamIntersect(4, ((1, 0.177), (2, 1.000)).into()),
-            BeamIntersect(2, ((1, 0.228), (1, 1.000)).into()),
-            BeamIntersect(4, ((2, 0.000), (2, 0.309)).into()),
-            BeamIntersect(0, ((1, 0.279), (0, 1.000)).into()),
-            BeamIntersect(3, ((2, 0.000), (1, 0.831)).into()),
-            BeamIntersect(1, ((2, 0.000), (0, 0.874)).into()),
-            BeamIntersect(4, ((3, 0.000), (1, 0.470)).into()),
-            BeamIntersect(3, ((3, 0.000), (0, 0.992)).into())
+            BeamIntersect(2, ((0, 0.087), (1, 1.000)).into()),
+            BeamIntersect(6, ((1, 0.177), (2, 1.000)).into()),
+            BeamIntersect(0, ((0, 0.138), (0, 1.000)).into()),
+            BeamIntersect(4, ((1, 0.228), (1, 1.000)).into()),
+            BeamIntersect(6, ((2, 0.000), (2, 0.309)).into()),
+            BeamIntersect(1, ((1, 0.000), (0, 0.755)).into()),
+            BeamIntersect(5, ((2, 0.000), (1, 0.831)).into()),
+            BeamIntersect(3, ((2, 0.000), (0, 0.874)).into()),
+            BeamIntersect(6, ((3, 0.000), (1, 0.470)).into()),
+            BeamIntersect(5, ((3, 0.000), (0, 0.992)).into())
         ],
     );
 
@@ -71,13 +77,15 @@ fn cast_beam_4x4grid() {
 
     let center = TilePosition::from(((3, 0.3), (1, 0.5)));
     let angle = 135_f32.to_radians();
+    // The last ray originates right of the grid and enters it through its right edge
     assert_eq!(
         cast(&grid, &center, width, angle),
         [
             BeamIntersect(1, ((2, 0.763), (1, 0.000)).into()),
-            BeamIntersect(4, ((3, 0.319), (2, 0.000)).into()),
+            BeamIntersect(6, ((3, 1.000), (2, 0.356)).into()),
             BeamIntersect(3, ((2, 0.800), (2, 0.000)).into()),
             BeamIntersect(0, ((1, 1.000), (1, 0.244)).into()),
+            BeamIntersect(6, ((3, 0.356), (3, 0.000)).into()),
             BeamIntersect(5, ((2, 0.837), (3, 0.000)).into()),
             BeamIntersect(1, ((1, 0.763), (2, 0.000)).into()),
             BeamIntersect(3, ((1, 0.800), (3, 0.000)).into()),
@@ -86,3 +94,355 @@ fn cast_beam_4x4grid() {
         ],
     );
 }
+
+#[test]
+fn cast_beam_ranged_4x4grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    let bis: Vec<BeamIntersect> = tc
+        .cast_beam_ranged(&center, 0.8, 0.0, 1.2)
+        .unwrap()
+        .map(round_beam_intersect)
+        .collect();
+    assert_eq!(
+        bis,
+        [
+            BeamIntersect(0, ((2, 0.000), (1, 0.900)).into()),
+            BeamIntersect(0, ((2, 0.700), (1, 0.900)).into())
+        ],
+    );
+}
+
+#[test]
+fn cast_beam_hits_4x4grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    let hits: Vec<(usize, f32)> = tc
+        .cast_beam(&center, 0.8, 0.0)
+        .unwrap()
+        .hits()
+        .map(|BeamHit(idx, hit)| (idx, round(hit.distance, 3)))
+        .collect();
+    assert_eq!(hits, [(0, 0.5), (0, 1.5)]);
+}
+
+#[test]
+fn cast_beam_direction_vector() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    let bis: Vec<BeamIntersect> = tc.cast_beam(&center, 0.8, (0.0, 3.0)).unwrap().collect();
+    // The beam advances straight up without drifting sideways
+    assert_eq!(
+        bis,
+        [
+            BeamIntersect(0, ((1, 0.100), (2, 0.000)).into()),
+            BeamIntersect(0, ((1, 0.100), (3, 0.000)).into())
+        ],
+    );
+}
+
+fn round_swept(SweptTile { tp, distance }: SweptTile) -> (TilePosition, f32) {
+    (round_tp(tp), round(distance, 3))
+}
+
+#[test]
+fn cast_beam_swept_4x4grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    let tiles: Vec<(TilePosition, f32)> = tc
+        .cast_beam_swept(&center, 0.8, 0.0)
+        .unwrap()
+        .map(round_swept)
+        .collect();
+    // Includes the tile the beam starts in which rays never enter
+    assert_eq!(
+        tiles,
+        [
+            (((1, 0.500), (1, 0.500)).into(), 0.0),
+            (((2, 0.000), (1, 0.500)).into(), 0.5),
+            (((3, 0.000), (1, 0.500)).into(), 1.5)
+        ],
+    );
+}
+
+#[test]
+fn cast_beam_swept_wide_4x4grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    let tiles: Vec<(TilePosition, f32)> = tc
+        .cast_beam_swept(&center, 1.2, 0.0)
+        .unwrap()
+        .map(round_swept)
+        .collect();
+    assert_eq!(
+        tiles,
+        [
+            (((1, 0.500), (0, 1.000)).into(), 0.0),
+            (((1, 0.500), (1, 0.500)).into(), 0.0),
+            (((1, 0.500), (2, 0.000)).into(), 0.0),
+            (((2, 0.000), (0, 1.000)).into(), 0.5),
+            (((2, 0.000), (1, 0.500)).into(), 0.5),
+            (((2, 0.000), (2, 0.000)).into(), 0.5),
+            (((3, 0.000), (0, 1.000)).into(), 1.5),
+            (((3, 0.000), (1, 0.500)).into(), 1.5),
+            (((3, 0.000), (2, 0.000)).into(), 1.5)
+        ],
+    );
+}
+
+#[test]
+fn cast_beam_swept_ranged_4x4grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    let tiles: Vec<(TilePosition, f32)> = tc
+        .cast_beam_swept_ranged(&center, 0.8, 0.0, 1.2)
+        .unwrap()
+        .map(round_swept)
+        .collect();
+    assert_eq!(
+        tiles,
+        [
+            (((1, 0.500), (1, 0.500)).into(), 0.0),
+            (((2, 0.000), (1, 0.500)).into(), 0.5)
+        ],
+    );
+}
+
+#[test]
+fn cast_beam_swept_ranged_beyond_grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    let unranged: Vec<SweptTile> = tc.cast_beam_swept(&center, 0.8, 0.7).unwrap().collect();
+    for max_distance in &[1e8, f32::INFINITY] {
+        let tiles: Vec<SweptTile> = tc
+            .cast_beam_swept_ranged(&center, 0.8, 0.7, *max_distance)
+            .unwrap()
+            .collect();
+        assert_eq!(tiles, unranged);
+    }
+    assert!(matches!(
+        tc.cast_beam_swept_ranged(&center, 0.8, 0.7, f32::NAN).err(),
+        Some(CrissCrossError::InvalidDistance(_))
+    ));
+    assert_eq!(
+        tc.cast_beam_swept_ranged(&center, 0.8, 0.7, -1.0).err(),
+        Some(CrissCrossError::InvalidDistance(-1.0))
+    );
+}
+
+#[test]
+#[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+fn cast_beam_swept_covers_ray_tiles() {
+    let tc = TileRaycaster::new(Grid::new(6, 6, 1.0).unwrap());
+    let center = TilePosition::from(((2, 0.3), (3, 0.6)));
+    for width in &[0.5, 1.0, 2.5] {
+        for degrees in (0..360).step_by(5) {
+            let angle = (degrees as f32).to_radians();
+            let tiles: Vec<SweptTile> = tc
+                .cast_beam_swept(&center, *width, angle)
+                .unwrap()
+                .collect();
+            for pair in tiles.windows(2) {
+                if let [first, second] = pair {
+                    assert!(first.distance <= second.distance, "{:?}", pair);
+                    assert!(!first.tp.is_same_tile(&second.tp) || first == second);
+                }
+            }
+            for BeamIntersect(_, tp) in tc.cast_beam(&center, *width, angle).unwrap() {
+                assert!(
+                    tiles.iter().any(|tile| tile.tp.is_same_tile(&tp)),
+                    "{:?} missing at {}° with width {}",
+                    tp,
+                    degrees,
+                    width
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn cast_beam_swept_wrapping_grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap().with_wrapping());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    assert_eq!(
+        tc.cast_beam_swept(&center, 0.8, 0.0).err(),
+        Some(CrissCrossError::UnboundedRay)
+    );
+    // Tiles covered again after lapping the grid are only included once
+    let tiles: Vec<(TilePosition, f32)> = tc
+        .cast_beam_swept_ranged(&center, 0.8, 0.0, 6.0)
+        .unwrap()
+        .map(round_swept)
+        .collect();
+    assert_eq!(
+        tiles,
+        [
+            (((1, 0.500), (1, 0.500)).into(), 0.0),
+            (((2, 0.000), (1, 0.500)).into(), 0.5),
+            (((3, 0.000), (1, 0.500)).into(), 1.5),
+            (((0, 0.000), (1, 0.500)).into(), 2.5)
+        ],
+    );
+}
+
+#[test]
+fn cast_beam_swept_touching_tiles() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    // The sides of the beam run along the edges of the tiles above and below without covering
+    // any part of them
+    let tiles: Vec<(u32, u32)> = tc
+        .cast_beam_swept(&center, 1.0, 0.0)
+        .unwrap()
+        .map(|SweptTile { tp, .. }| (tp.x, tp.y))
+        .collect();
+    assert_eq!(tiles, [(1, 1), (2, 1), (3, 1)]);
+}
+
+#[test]
+fn cast_beam_occluded_by_pillar() {
+    let tc = TileRaycaster::new(Grid::new(6, 6, 1.0).unwrap());
+    let center = TilePosition::from(((0, 0.5), (2, 0.4)));
+    let is_valid = |BeamIntersect(_, tp): &BeamIntersect| !(tp.x == 3 && tp.y == 2);
+    let mut bis = tc.cast_beam_occluded(&center, 2.0, 0.0, is_valid).unwrap();
+    let tiles: Vec<(usize, u32, u32)> = bis
+        .by_ref()
+        .map(|BeamIntersect(idx, tp)| (idx, tp.x, tp.y))
+        .collect();
+    // Only the rays entering the pillar stop, the ones passing above and below keep going
+    assert_eq!(
+        tiles,
+        [
+            (0, 1, 3),
+            (1, 1, 2),
+            (3, 1, 1),
+            (0, 2, 3),
+            (1, 2, 2),
+            (3, 2, 1),
+            (0, 3, 3),
+            (3, 3, 1),
+            (0, 4, 3),
+            (3, 4, 1),
+            (0, 5, 3),
+            (3, 5, 1)
+        ],
+    );
+    let BeamCrossing { crossings, nearest } = bis.into_beam_crossing();
+    let crossings: Vec<Crossing> = crossings.into_iter().map(round_cutoff).collect();
+    assert_eq!(
+        crossings,
+        [
+            Crossing {
+                valid: Some(((5, 0.000), (3, 0.400)).into()),
+                invalid: None,
+            },
+            Crossing {
+                valid: Some(((2, 0.000), (2, 0.900)).into()),
+                invalid: Some(((3, 0.000), (2, 0.900)).into()),
+            },
+            Crossing {
+                valid: Some(((2, 0.000), (2, 0.400)).into()),
+                invalid: Some(((3, 0.000), (2, 0.400)).into()),
+            },
+            Crossing {
+                valid: Some(((5, 0.000), (1, 0.900)).into()),
+                invalid: None,
+            },
+            Crossing {
+                valid: Some(((5, 0.000), (1, 0.400)).into()),
+                invalid: None,
+            }
+        ],
+    );
+    let BeamHit(idx, hit) = nearest.unwrap();
+    assert_eq!(
+        (idx, round_tp(hit.tp), round(hit.distance, 3)),
+        (1, ((3, 0.000), (2, 0.900)).into(), 2.5)
+    );
+}
+
+// Origin, start, offset and angle of each ray
+type BeamRayLayout = ((f32, f32), TilePosition, f32, f32);
+
+fn layout(tc: &TileRaycaster, center: &TilePosition, width: f32, angle: f32) -> Vec<BeamRayLayout> {
+    tc.beam_layout(center, width, angle)
+        .unwrap()
+        .rays
+        .into_iter()
+        .map(|ray| {
+            (
+                (round(ray.origin.0, 3), round(ray.origin.1, 3)),
+                round_tp(ray.start),
+                round(ray.offset, 3),
+                round(ray.direction.angle().0, 3),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn beam_layout_4x4grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((1, 0.5), (1, 0.5)));
+    assert_eq!(
+        round(tc.beam_layout(&center, 0.8, 0.0).unwrap().spacing, 3),
+        0.4
+    );
+    // The first ray is cast from the left of the direction
+    assert_eq!(
+        layout(&tc, &center, 0.8, 0.0),
+        [
+            ((1.5, 1.9), ((1, 0.500), (1, 0.900)).into(), 0.4, 0.0),
+            ((1.5, 1.5), ((1, 0.500), (1, 0.500)).into(), 0.0, 0.0),
+            ((1.5, 1.1), ((1, 0.500), (1, 0.100)).into(), -0.4, 0.0)
+        ],
+    );
+
+    // Origins are absolute world coordinates
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap().with_origin(10.0, -2.0));
+    assert_eq!(
+        layout(&tc, &center, 0.8, 0.0),
+        [
+            ((11.5, -0.1), ((1, 0.500), (1, 0.900)).into(), 0.4, 0.0),
+            ((11.5, -0.5), ((1, 0.500), (1, 0.500)).into(), 0.0, 0.0),
+            ((11.5, -0.9), ((1, 0.500), (1, 0.100)).into(), -0.4, 0.0)
+        ],
+    );
+}
+
+#[test]
+#[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+fn beam_layout_outside_of_grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let center = TilePosition::from(((0, 0.0), (0, 0.0)));
+    let angle = 30_f32.to_radians();
+    let rays = layout(&tc, &center, 2.0, angle);
+    // The rays on the sides originate outside of the grid and start where they enter it
+    assert_eq!(
+        rays,
+        [
+            ((-0.5, 0.866), ((0, 0.000), (1, 0.155)).into(), 1.0, 0.524),
+            ((-0.25, 0.433), ((0, 0.000), (0, 0.577)).into(), 0.5, 0.524),
+            ((0.0, 0.0), ((0, 0.000), (0, 0.000)).into(), 0.0, 0.524),
+            ((0.25, -0.433), ((1, 0.000), (0, 0.000)).into(), -0.5, 0.524),
+            ((0.5, -0.866), ((2, 0.000), (0, 0.000)).into(), -1.0, 0.524)
+        ],
+    );
+    // Each intersection lies on the ray with the same index in the layout
+    let rays = tc.beam_layout(&center, 2.0, angle).unwrap().rays;
+    for BeamIntersect(idx, tp) in tc.cast_beam(&center, 2.0, angle).unwrap() {
+        let BeamRay {
+            origin, direction, ..
+        } = &rays[idx];
+        let (x, y) = (tp.x as f32 + tp.rel_x, tp.y as f32 + tp.rel_y);
+        let (dx, dy) = (x - origin.0, y - origin.1);
+        assert!(
+            direction.x().mul_add(dy, -direction.y() * dx).abs() < 1e-3,
+            "{:?} is not on ray {}",
+            tp,
+            idx
+        );
+        assert!(direction.x().mul_add(dx, direction.y() * dy) >= 0.0);
+    }
+}
@@ -0,0 +1,99 @@
+mod common;
+use common::{round_beam_intersect, round_tp};
+use crisscross::{BeamIntersect, Grid, TilePosition, TileRaycaster};
+
+fn tiles(bis: impl Iterator<Item = BeamIntersect>) -> Vec<(u32, u32)> {
+    bis.map(|BeamIntersect(_, tp)| (tp.x, tp.y)).collect()
+}
+
+#[test]
+fn cast_cone_5x5grid() {
+    let tc = TileRaycaster::new(Grid::new(5, 5, 1.0).unwrap());
+    let origin = TilePosition::from(((0, 0.5), (2, 0.5)));
+    let bis: Vec<BeamIntersect> = tc
+        .cast_cone(&origin, 0.0, 30_f32.to_radians())
+        .unwrap()
+        .map(round_beam_intersect)
+        .collect();
+    assert_eq!(
+        bis[..3],
+        [
+            BeamIntersect(6, ((1, 0.000), (2, 0.500)).into()),
+            BeamIntersect(0, ((1, 0.366), (3, 0.000)).into()),
+            BeamIntersect(12, ((1, 0.366), (1, 1.000)).into()),
+        ],
+    );
+    // The cone widens with the distance, thus (2, 4) and (2, 0) are never reached
+    assert_eq!(
+        tiles(bis.into_iter()),
+        [
+            (1, 2),
+            (1, 3),
+            (1, 1),
+            (2, 2),
+            (2, 1),
+            (2, 3),
+            (3, 2),
+            (3, 1),
+            (3, 3),
+            (3, 4),
+            (3, 0),
+            (4, 2),
+            (4, 1),
+            (4, 3),
+            (4, 0),
+            (4, 4)
+        ],
+    );
+}
+
+#[test]
+fn cast_cone_ranged_5x5grid() {
+    let tc = TileRaycaster::new(Grid::new(5, 5, 1.0).unwrap());
+    let origin = TilePosition::from(((0, 0.5), (2, 0.5)));
+    let bis = tc
+        .cast_cone_ranged(&origin, 0.0, 30_f32.to_radians(), 2.5)
+        .unwrap();
+    assert_eq!(
+        tiles(bis),
+        [(1, 2), (1, 3), (1, 1), (2, 2), (2, 1), (2, 3), (3, 2)],
+    );
+}
+
+#[test]
+fn cast_cone_without_spread() {
+    let tc = TileRaycaster::new(Grid::new(5, 5, 1.0).unwrap());
+    let origin = TilePosition::from(((0, 0.5), (2, 0.5)));
+    let angle = 20_f32.to_radians();
+    let cone: Vec<TilePosition> = tc
+        .cast_cone(&origin, angle, 0.0)
+        .unwrap()
+        .map(|BeamIntersect(idx, tp)| {
+            assert_eq!(idx, 0);
+            round_tp(tp)
+        })
+        .collect();
+    let ray: Vec<TilePosition> = tc.cast_ray(&origin, angle).unwrap().map(round_tp).collect();
+    assert_eq!(cone, ray);
+}
+
+#[test]
+fn cast_cone_yields_tiles_once() {
+    let tc = TileRaycaster::new(Grid::new(9, 9, 1.0).unwrap());
+    let origin = TilePosition::from(((4, 0.2), (4, 0.7)));
+    for half_angle in &[10_f32, 45.0, 90.0, 180.0] {
+        let mut tiles = tiles(
+            tc.cast_cone(&origin, 70_f32.to_radians(), half_angle.to_radians())
+                .unwrap(),
+        );
+        let len = tiles.len();
+        tiles.sort_unstable();
+        tiles.dedup();
+        assert_eq!(tiles.len(), len, "half angle {half_angle}");
+    }
+    // A full circle reaches every tile except the one it starts in
+    assert_eq!(
+        tiles(tc.cast_cone(&origin, 0.0, std::f32::consts::PI).unwrap()).len(),
+        80
+    );
+}
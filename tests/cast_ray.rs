@@ -1,10 +1,13 @@
 mod common;
-use common::round_tp;
-use crisscross::{Grid, TilePosition, TileRaycaster};
+use common::{round, round_tp};
+use crisscross::{
+    CrissCrossError, Grid, RayHit, RayHitIter, TileEdge, TilePosition, TileRaycaster,
+};
 
 fn cast(tc: &TileRaycaster, origin: &TilePosition, angle: f32) -> Vec<TilePosition> {
     let tps: Vec<TilePosition> = tc
         .cast_ray(origin, angle)
+        .unwrap()
         .map(round_tp)
         .collect::<Vec<TilePosition>>();
     #[cfg(feature = "plot")]
@@ -24,7 +27,7 @@ fn cast(tc: &TileRaycaster, origin: &TilePosition, angle: f32) -> Vec<TilePositi
 
 #[test]
 fn grid_4x4_0deg() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 0_f32.to_radians());
     assert_eq!(
         cast(&tc, tp, angle),
@@ -47,7 +50,7 @@ fn grid_4x4_0deg() {
 
 #[test]
 fn grid_4x4_30deg() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 30_f32.to_radians());
     assert_eq!(
         cast(&tc, tp, angle),
@@ -73,7 +76,7 @@ fn grid_4x4_30deg() {
 
 #[test]
 fn grid_4x4_45deg() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 45_f32.to_radians());
     assert_eq!(
         cast(&tc, tp, angle),
@@ -86,7 +89,7 @@ fn grid_4x4_45deg() {
 
 #[test]
 fn grid_4x4_60deg() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 60_f32.to_radians());
     assert_eq!(
         cast(&tc, tp, angle),
@@ -100,9 +103,10 @@ fn grid_4x4_60deg() {
 
 #[test]
 fn grid_4x4_90deg() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     assert_eq!(
         tc.cast_ray(&((1, 0.5), (1, 0.5)).into(), 90_f32.to_radians())
+            .unwrap()
             .map(round_tp)
             .collect::<Vec<TilePosition>>(),
         [
@@ -114,7 +118,7 @@ fn grid_4x4_90deg() {
 
 #[test]
 fn grid_4x4_150deg() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 150_f32.to_radians());
     assert_eq!(
         cast(&tc, tp, angle),
@@ -127,7 +131,7 @@ fn grid_4x4_150deg() {
 
 #[test]
 fn grid_4x4_210deg() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 210_f32.to_radians());
     assert_eq!(
         cast(&tc, tp, angle),
@@ -140,7 +144,7 @@ fn grid_4x4_210deg() {
 
 #[test]
 fn grid_4x4_330deg() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 330_f32.to_radians());
     assert_eq!(
         cast(&tc, tp, angle),
@@ -163,3 +167,441 @@ fn grid_4x4_330deg() {
         ],
     );
 }
+
+fn cast_ranged(
+    tc: &TileRaycaster,
+    origin: &TilePosition,
+    angle: f32,
+    max_distance: f32,
+) -> Vec<TilePosition> {
+    tc.cast_ray_ranged(origin, angle, max_distance)
+        .unwrap()
+        .map(round_tp)
+        .collect()
+}
+
+#[test]
+fn grid_4x4_ranged() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let (tp, angle) = (&((0, 0.0), (0, 0.0)).into(), 30_f32.to_radians());
+    assert_eq!(
+        cast_ranged(&tc, tp, angle, 2.5),
+        [
+            ((1, 0.000), (0, 0.577)).into(),
+            ((1, 0.732), (1, 0.000)).into(),
+            ((2, 0.000), (1, 0.155)).into(),
+            ((2, 0.165), (1, 0.250)).into()
+        ],
+    );
+
+    // end lies in same tile as origin
+    let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 0_f32.to_radians());
+    assert_eq!(
+        cast_ranged(&tc, tp, angle, 0.25),
+        [((1, 0.750), (1, 0.500)).into()],
+    );
+
+    // end lies exactly on the last intersection
+    let (tp, angle) = (&((0, 0.0), (0, 0.0)).into(), 0_f32.to_radians());
+    assert_eq!(
+        cast_ranged(&tc, tp, angle, 2.0),
+        [
+            ((1, 0.000), (0, 0.000)).into(),
+            ((2, 0.000), (0, 0.000)).into()
+        ],
+    );
+
+    // ray leaves the grid before reaching its max distance
+    let (tp, angle) = (&((1, 0.5), (1, 0.5)).into(), 180_f32.to_radians());
+    assert_eq!(
+        cast_ranged(&tc, tp, angle, 10.0),
+        [((0, 1.000), (1, 0.500)).into()],
+    );
+}
+
+#[test]
+fn grid_4x4_segment() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let from = ((0, 0.0), (0, 0.0)).into();
+    let to = ((3, 0.464), (2, 0.0)).into();
+    assert_eq!(
+        tc.cast_segment(&from, &to)
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [
+            ((1, 0.000), (0, 0.577)).into(),
+            ((1, 0.732), (1, 0.000)).into(),
+            ((2, 0.000), (1, 0.155)).into(),
+            ((3, 0.000), (1, 0.732)).into(),
+            ((3, 0.464), (2, 0.000)).into()
+        ],
+    );
+
+    let from = ((1, 0.5), (1, 0.5)).into();
+    let to = ((0, 0.5), (3, 0.5)).into();
+    assert_eq!(
+        tc.cast_segment(&from, &to)
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [
+            ((1, 0.250), (2, 0.000)).into(),
+            ((0, 1.000), (2, 0.500)).into(),
+            ((0, 0.750), (3, 0.000)).into(),
+            ((0, 0.500), (3, 0.500)).into()
+        ],
+    );
+
+    // target in same tile
+    let to = ((1, 0.8), (1, 0.1)).into();
+    assert_eq!(
+        tc.cast_segment(&from, &to)
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [((1, 0.800), (1, 0.100)).into()],
+    );
+}
+
+#[test]
+fn grid_4x4_segment_to_edge_approached_from_right() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    // The target lies on the left edge of tile 2, the segment never enters tile 1
+    let from = ((3, 0.5), (1, 0.5)).into();
+    let to = ((2, 0.0), (1, 0.5)).into();
+    assert_eq!(
+        tc.cast_segment(&from, &to)
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [
+            ((2, 1.000), (1, 0.500)).into(),
+            ((2, 0.000), (1, 0.500)).into()
+        ],
+    );
+
+    // Same for the bottom edge approached from above
+    let from = ((1, 0.5), (3, 0.5)).into();
+    let to = ((1, 0.5), (1, 0.0)).into();
+    assert_eq!(
+        tc.cast_segment(&from, &to)
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [
+            ((1, 0.500), (2, 1.000)).into(),
+            ((1, 0.500), (1, 1.000)).into(),
+            ((1, 0.500), (1, 0.000)).into()
+        ],
+    );
+}
+
+#[test]
+fn grid_4x4_hit_edges() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let origin = ((0, 0.0), (0, 0.0)).into();
+    let hits: Vec<(TilePosition, Option<TileEdge>)> = tc
+        .cast_ray_ranged(&origin, 30_f32.to_radians(), 2.5)
+        .unwrap()
+        .hits()
+        .map(|RayHit { tp, edge, .. }| (round_tp(tp), edge))
+        .collect();
+    assert_eq!(
+        hits,
+        [
+            (((1, 0.000), (0, 0.577)).into(), Some(TileEdge::Left)),
+            (((1, 0.732), (1, 0.000)).into(), Some(TileEdge::Bottom)),
+            (((2, 0.000), (1, 0.155)).into(), Some(TileEdge::Left)),
+            (((2, 0.165), (1, 0.250)).into(), None)
+        ],
+    );
+}
+
+#[test]
+fn grid_4x4_hit_distances() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let origin = ((0, 0.0), (0, 0.0)).into();
+
+    let distances = |hits: RayHitIter| -> Vec<(f32, f32)> {
+        hits.map(|hit| (round(hit.distance, 3), round(hit.t, 3)))
+            .collect()
+    };
+
+    // ray leaves the grid at (4.0, 4.0)
+    assert_eq!(
+        distances(tc.cast_ray(&origin, 45_f32.to_radians()).unwrap().hits()),
+        [(1.414, 0.25), (2.828, 0.5), (4.243, 0.75)]
+    );
+
+    // ranged ray ends at max distance
+    assert_eq!(
+        distances(tc.cast_ray_ranged(&origin, 0.0, 2.5).unwrap().hits()),
+        [(1.0, 0.4), (2.0, 0.8), (2.5, 1.0)]
+    );
+
+    // segment ends at target
+    let to = ((2, 0.0), (0, 0.0)).into();
+    assert_eq!(
+        distances(tc.cast_segment(&origin, &to).unwrap().hits()),
+        [(1.0, 0.5), (2.0, 1.0)]
+    );
+}
+
+#[test]
+fn grid_3x2_rectangular_tiles() {
+    let tc = TileRaycaster::new(Grid::new(3, 2, (2.0, 1.0)).unwrap());
+
+    let origin = ((0, 1.0), (0, 0.5)).into();
+    assert_eq!(
+        tc.cast_ray(&origin, 45_f32.to_radians())
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [
+            ((0, 1.500), (1, 0.000)).into(),
+            ((1, 0.000), (1, 0.500)).into(),
+        ],
+    );
+
+    let origin = ((2, 1.0), (0, 0.5)).into();
+    assert_eq!(
+        tc.cast_ray(&origin, 180_f32.to_radians())
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [
+            ((1, 2.000), (0, 0.500)).into(),
+            ((0, 2.000), (0, 0.500)).into(),
+        ],
+    );
+
+    let origin = ((1, 1.0), (0, 0.5)).into();
+    assert_eq!(
+        tc.cast_ray(&origin, 270_f32.to_radians())
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [],
+    );
+    assert_eq!(
+        tc.cast_ray(&origin, 90_f32.to_radians())
+            .unwrap()
+            .map(round_tp)
+            .collect::<Vec<TilePosition>>(),
+        [((1, 1.000), (1, 0.000)).into()],
+    );
+}
+
+fn round_point((x, y): (f32, f32)) -> (f32, f32) {
+    (round(x, 3), round(y, 3))
+}
+
+#[test]
+fn grid_4x4_with_origin() {
+    let grid = Grid::new(4, 4, 1.0).unwrap().with_origin(100.0, -50.0);
+    assert_eq!(
+        grid.tile_position_at(101.5, -48.5).map(round_tp),
+        Some(((1, 0.5), (1, 0.5)).into())
+    );
+    assert_eq!(grid.tile_position_at(99.5, -48.5), None);
+    assert_eq!(grid.tile_position_at(101.5, -46.0), None);
+    assert_eq!(
+        round_point(grid.world_position(&((3, 0.25), (0, 0.75)).into())),
+        (103.25, -49.25)
+    );
+
+    let tc = TileRaycaster::new(grid);
+    assert_eq!(
+        tc.cast_ray_world((101.5, -48.5), 30_f32.to_radians())
+            .unwrap()
+            .map(round_point)
+            .collect::<Vec<_>>(),
+        [(102.0, -48.211), (102.366, -48.0), (103.0, -47.634)],
+    );
+    assert_eq!(
+        tc.cast_segment_world((101.5, -48.5), (101.5, -46.5))
+            .unwrap()
+            .map(round_point)
+            .collect::<Vec<_>>(),
+        [(101.5, -48.0), (101.5, -47.0), (101.5, -46.5)],
+    );
+}
+
+#[test]
+fn grid_4x4_from_outside() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap().with_origin(100.0, -50.0));
+    assert_eq!(
+        tc.cast_ray_world((90.0, -48.5), 0.0)
+            .unwrap()
+            .map(round_point)
+            .collect::<Vec<_>>(),
+        [
+            (100.0, -48.5),
+            (101.0, -48.5),
+            (102.0, -48.5),
+            (103.0, -48.5)
+        ],
+    );
+    assert_eq!(
+        tc.cast_ray_world((102.5, -40.0), 270_f32.to_radians())
+            .unwrap()
+            .map(round_point)
+            .collect::<Vec<_>>(),
+        [
+            (102.5, -46.0),
+            (102.5, -47.0),
+            (102.5, -48.0),
+            (102.5, -49.0)
+        ],
+    );
+    assert_eq!(
+        tc.cast_ray_world((106.0, -47.5), 210_f32.to_radians())
+            .unwrap()
+            .map(round_point)
+            .collect::<Vec<_>>(),
+        [
+            (104.0, -48.655),
+            (103.402, -49.0),
+            (103.0, -49.232),
+            (102.0, -49.809)
+        ],
+    );
+    assert_eq!(
+        tc.cast_segment_world((101.5, -55.0), (101.5, -48.5))
+            .unwrap()
+            .map(round_point)
+            .collect::<Vec<_>>(),
+        [(101.5, -50.0), (101.5, -49.0), (101.5, -48.5)],
+    );
+
+    // pointing away from or passing by the grid
+    assert_eq!(
+        tc.cast_ray_world((90.0, -48.5), 180_f32.to_radians())
+            .unwrap()
+            .count(),
+        0
+    );
+    assert_eq!(tc.cast_ray_world((90.0, -55.0), 0.0).unwrap().count(), 0);
+    assert_eq!(
+        tc.cast_ray_world((90.0, -48.5), 90_f32.to_radians())
+            .unwrap()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn grid_4x4_wrapping() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap().with_wrapping());
+    let hits = |origin: &TilePosition, angle: f32, max_distance: f32| {
+        tc.cast_ray_ranged(origin, angle.to_radians(), max_distance)
+            .unwrap()
+            .hits()
+            .map(
+                |RayHit {
+                     tp, edge, distance, ..
+                 }| (round_tp(tp), edge, round(distance, 3)),
+            )
+            .collect::<Vec<_>>()
+    };
+
+    // re-enters through the left edge
+    assert_eq!(
+        hits(&((3, 0.5), (1, 0.5)).into(), 0.0, 3.0),
+        [
+            (((0, 0.000), (1, 0.500)).into(), Some(TileEdge::Left), 0.5),
+            (((1, 0.000), (1, 0.500)).into(), Some(TileEdge::Left), 1.5),
+            (((2, 0.000), (1, 0.500)).into(), Some(TileEdge::Left), 2.5),
+            (((2, 0.500), (1, 0.500)).into(), None, 3.0)
+        ],
+    );
+
+    // re-enters through the right edge
+    assert_eq!(
+        hits(&((0, 0.5), (1, 0.5)).into(), 180.0, 2.0),
+        [
+            (((3, 1.000), (1, 0.500)).into(), Some(TileEdge::Right), 0.5),
+            (((2, 1.000), (1, 0.500)).into(), Some(TileEdge::Right), 1.5),
+            (((2, 0.500), (1, 0.500)).into(), None, 2.0)
+        ],
+    );
+
+    // re-enters through the bottom left corner
+    assert_eq!(
+        hits(&((3, 0.5), (3, 0.5)).into(), 45.0, 2.0),
+        [
+            (
+                ((0, 0.000), (0, 0.000)).into(),
+                Some(TileEdge::BottomLeft),
+                0.707
+            ),
+            (((0, 0.914), (0, 0.914)).into(), None, 2.0)
+        ],
+    );
+
+    // laps the grid
+    assert_eq!(hits(&((1, 0.5), (0, 0.5)).into(), 90.0, 8.0).len(), 9);
+}
+
+#[test]
+fn grid_4x4_wrapping_needs_range() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap().with_wrapping());
+    let origin = ((1, 0.5), (1, 0.5)).into();
+    assert_eq!(
+        tc.cast_ray(&origin, 0.0).err(),
+        Some(CrissCrossError::UnboundedRay)
+    );
+    assert!(tc.cast_beam(&origin, 0.5, 0.0).is_err());
+}
+
+#[test]
+fn grid_4x4_direction_vectors() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let cast_vector = |origin: &TilePosition, direction: (f32, f32)| {
+        tc.cast_ray(origin, direction)
+            .unwrap()
+            .collect::<Vec<TilePosition>>()
+    };
+
+    // vertical and horizontal vectors step along exactly one axis without rounding errors
+    let tp = ((1, 0.3), (0, 0.7)).into();
+    assert_eq!(
+        cast_vector(&tp, (0.0, 2.0)),
+        [
+            ((1, 0.3), (1, 0.0)).into(),
+            ((1, 0.3), (2, 0.0)).into(),
+            ((1, 0.3), (3, 0.0)).into()
+        ],
+    );
+    let tp = ((1, 0.3), (3, 0.7)).into();
+    assert_eq!(
+        cast_vector(&tp, (0.0, -0.5)),
+        [
+            ((1, 0.3), (2, 1.0)).into(),
+            ((1, 0.3), (1, 1.0)).into(),
+            ((1, 0.3), (0, 1.0)).into()
+        ],
+    );
+    let tp = ((3, 0.6), (2, 0.1)).into();
+    assert_eq!(
+        cast_vector(&tp, (-4.0, 0.0)),
+        [
+            ((2, 1.0), (2, 0.1)).into(),
+            ((1, 1.0), (2, 0.1)).into(),
+            ((0, 1.0), (2, 0.1)).into()
+        ],
+    );
+
+    // velocities don't need to be normalized
+    let rounded = |tps: Vec<TilePosition>| tps.into_iter().map(round_tp).collect::<Vec<_>>();
+    let tp = ((0, 0.5), (0, 0.5)).into();
+    assert_eq!(
+        rounded(cast_vector(&tp, (3.0, 4.0))),
+        cast(&tc, &tp, 4_f32.atan2(3.0)),
+    );
+    assert_eq!(
+        rounded(cast_vector(&tp, (0.3, 0.3))),
+        cast(&tc, &tp, 45_f32.to_radians()),
+    );
+}
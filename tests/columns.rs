@@ -0,0 +1,69 @@
+mod common;
+use common::round;
+use crisscross::{BitGrid, Camera, Grid, TileEdge, TilePosition, TileRaycaster};
+
+fn room() -> TileRaycaster<BitGrid> {
+    let grid = Grid::new(5, 5, 1.0).unwrap();
+    let mut map = BitGrid::new(&grid);
+    for idx in 0..5 {
+        map.set(idx, 0, true);
+        map.set(idx, 4, true);
+        map.set(0, idx, true);
+        map.set(4, idx, true);
+    }
+    TileRaycaster::with_map(grid, map)
+}
+
+#[test]
+fn facing_flat_wall() {
+    let tc = room();
+    let camera = Camera::new(TilePosition::new(2, 2, 0.5, 0.5), 0.0, 60_f32.to_radians());
+    let columns = tc.cast_columns_blocked(&camera, 5).unwrap();
+    assert_eq!(columns.len(), 5);
+
+    let hits: Vec<(u32, u32, TileEdge, f32, f32)> = columns
+        .iter()
+        .map(|column| {
+            let hit = column.hit.as_ref().unwrap();
+            (
+                hit.tp.x,
+                hit.tp.y,
+                hit.edge,
+                round(hit.perpendicular_distance, 3),
+                round(hit.texture_u, 3),
+            )
+        })
+        .collect();
+
+    // No fish-eye, i.e. the wall is equally far away for every column, and texture u
+    // increases from left to right within each wall tile
+    assert_eq!(
+        hits,
+        [
+            (4, 3, TileEdge::Left, 1.5, 0.807),
+            (4, 2, TileEdge::Left, 1.5, 0.154),
+            (4, 2, TileEdge::Left, 1.5, 0.5),
+            (4, 2, TileEdge::Left, 1.5, 0.846),
+            (4, 1, TileEdge::Left, 1.5, 0.193),
+        ]
+    );
+
+    let center = columns[2].hit.as_ref().unwrap();
+    assert_eq!(round(center.distance, 3), 1.5);
+    // Projection plane is 2.5 / tan(30°) = 4.330 pixels away
+    assert_eq!(round(center.wall_height, 3), 2.887);
+    let outer = columns[0].hit.as_ref().unwrap();
+    assert!(outer.distance > center.distance);
+}
+
+#[test]
+fn facing_up() {
+    let tc = room();
+    let camera = Camera::new(TilePosition::new(2, 1, 0.25, 0.5), 90_f32.to_radians(), 1.0);
+    let columns = tc.cast_columns_blocked(&camera, 1).unwrap();
+    let hit = columns[0].hit.as_ref().unwrap();
+    assert_eq!((hit.tp.x, hit.tp.y), (2, 4));
+    assert_eq!(hit.edge, TileEdge::Bottom);
+    assert_eq!(round(hit.perpendicular_distance, 3), 2.5);
+    assert_eq!(round(hit.texture_u, 3), 0.25);
+}
@@ -1,5 +1,5 @@
 #![allow(unused)] // work around cargo bug
-use crisscross::{BeamIntersect, Crossing, TilePosition};
+use crisscross::{BeamIntersect, Crossing, SignedTilePosition, TilePosition};
 
 #[allow(
     clippy::as_conversions,
@@ -21,6 +21,16 @@ pub(crate) fn round_tp(tp: TilePosition) -> TilePosition {
     }
 }
 
+pub(crate) fn round_stp(stp: SignedTilePosition) -> SignedTilePosition {
+    let SignedTilePosition { x, y, rel_x, rel_y } = stp;
+    SignedTilePosition {
+        x,
+        y,
+        rel_x: round(rel_x, 3),
+        rel_y: round(rel_y, 3),
+    }
+}
+
 pub(crate) fn round_otp(tp: Option<TilePosition>) -> Option<TilePosition> {
     let TilePosition { x, y, rel_x, rel_y } = tp?;
     Some(TilePosition {
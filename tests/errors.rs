@@ -0,0 +1,167 @@
+use crisscross::{
+    Camera, CrissCrossError, Direction, Grid, TilePosition, TileRaycaster, WorldCoords,
+};
+
+#[test]
+fn invalid_tile_size() {
+    assert_eq!(
+        Grid::new(4, 4, 0.0).err(),
+        Some(CrissCrossError::InvalidTileSize {
+            width: 0.0,
+            height: 0.0
+        })
+    );
+    assert_eq!(
+        Grid::new(4, 4, (1.0, -2.0)).err(),
+        Some(CrissCrossError::InvalidTileSize {
+            width: 1.0,
+            height: -2.0
+        })
+    );
+    assert!(Grid::new(4, 4, f32::INFINITY).is_err());
+}
+
+#[test]
+fn origin_outside_of_grid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let tp = TilePosition::new(4, 1, 0.5, 0.5);
+    assert_eq!(
+        tc.cast_ray(&tp, 0.0).err(),
+        Some(CrissCrossError::TileOutOfBounds { x: 4, y: 1 })
+    );
+    assert_eq!(
+        tc.cast_segment(&TilePosition::new(1, 1, 0.5, 0.5), &tp)
+            .err(),
+        Some(CrissCrossError::TileOutOfBounds { x: 4, y: 1 })
+    );
+    assert!(tc.field_of_view(&tp, 2.0, |_| false).is_err());
+
+    let camera = Camera::new(TilePosition::new(1, 7, 0.5, 0.5), 0.0, 1.0);
+    assert_eq!(
+        tc.cast_columns(&camera, 10, |_| false).err(),
+        Some(CrissCrossError::TileOutOfBounds { x: 1, y: 7 })
+    );
+}
+
+#[test]
+fn invalid_direction() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let tp = TilePosition::new(1, 1, 0.5, 0.5);
+    assert_eq!(
+        tc.cast_ray(&tp, f32::NAN).err(),
+        Some(CrissCrossError::InvalidDirection)
+    );
+    assert_eq!(
+        tc.cast_beam(&tp, 0.5, f32::INFINITY).err(),
+        Some(CrissCrossError::InvalidDirection)
+    );
+    assert_eq!(
+        tc.cast_ray(&tp, (0.0, 0.0)).err(),
+        Some(CrissCrossError::InvalidDirection)
+    );
+    assert_eq!(
+        tc.cast_beam(&tp, 0.5, (f32::NAN, 1.0)).err(),
+        Some(CrissCrossError::InvalidDirection)
+    );
+    // Very large and very small vectors are normalized without overflowing or underflowing
+    assert_eq!(Direction::new(f32::MAX, f32::MAX), Direction::new(1.0, 1.0));
+    assert_eq!(Direction::new(0.0, -1e-45), Direction::new(0.0, -1.0));
+    assert!(!Direction::new(0.0, 0.0).is_valid());
+    assert_eq!(
+        tc.cast_ray(&tp, (f32::MAX, -f32::MAX))
+            .unwrap()
+            .collect::<Vec<_>>(),
+        tc.cast_ray(&tp, (1.0, -1.0)).unwrap().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn invalid_half_angle() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let tp = TilePosition::new(1, 1, 0.5, 0.5);
+    assert_eq!(
+        tc.cast_cone(&tp, 0.0, -0.1).err(),
+        Some(CrissCrossError::InvalidHalfAngle(-0.1))
+    );
+    assert_eq!(
+        tc.cast_cone_ranged(&tp, 0.0, 4.0, 2.0).err(),
+        Some(CrissCrossError::InvalidHalfAngle(4.0))
+    );
+    assert!(tc.cast_cone(&tp, 0.0, f32::NAN).is_err());
+    assert_eq!(
+        tc.cast_cone(&tp, f32::NAN, 0.5).err(),
+        Some(CrissCrossError::InvalidDirection)
+    );
+}
+
+#[test]
+fn invalid_beam_width() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let tp = TilePosition::new(1, 1, 0.5, 0.5);
+    assert_eq!(
+        tc.cast_beam(&tp, 0.0, 0.0).err(),
+        Some(CrissCrossError::InvalidWidth(0.0))
+    );
+    assert_eq!(
+        tc.cast_beam_ranged(&tp, -1.0, 0.0, 2.0).err(),
+        Some(CrissCrossError::InvalidWidth(-1.0))
+    );
+    assert_eq!(
+        tc.cast_beam_swept(&tp, 0.0, 0.0).err(),
+        Some(CrissCrossError::InvalidWidth(0.0))
+    );
+}
+
+#[test]
+fn invalid_distance() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let tp = TilePosition::new(1, 1, 0.5, 0.5);
+    assert_eq!(
+        tc.cast_ray_ranged(&tp, 0.0, -3.0).err(),
+        Some(CrissCrossError::InvalidDistance(-3.0))
+    );
+    assert!(matches!(
+        tc.cast_ray_ranged(&tp, 0.0, f32::NAN).err(),
+        Some(CrissCrossError::InvalidDistance(_))
+    ));
+    assert_eq!(
+        tc.cast_beam_ranged(&tp, 0.5, 0.0, -1.0).err(),
+        Some(CrissCrossError::InvalidDistance(-1.0))
+    );
+    assert_eq!(
+        tc.cast_cone_ranged(&tp, 0.0, 0.5, -1.0).err(),
+        Some(CrissCrossError::InvalidDistance(-1.0))
+    );
+    assert!(tc.cast_ray_ranged(&tp, 0.0, 0.0).is_ok());
+    assert_eq!(
+        tc.cast_ray_ranged(&tp, 0.0, f32::INFINITY)
+            .unwrap()
+            .collect::<Vec<_>>(),
+        tc.cast_ray(&tp, 0.0).unwrap().collect::<Vec<_>>()
+    );
+
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap().with_wrapping());
+    assert_eq!(
+        tc.cast_ray_ranged(&tp, 0.0, f32::INFINITY).err(),
+        Some(CrissCrossError::UnboundedRay)
+    );
+}
+
+#[test]
+fn non_finite_coordinates() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    assert!(matches!(
+        tc.cast_ray_world((f32::NAN, 1.0), 0.0).err(),
+        Some(CrissCrossError::NonFiniteCoordinates { x, y: _ }) if x.is_nan()
+    ));
+    assert_eq!(
+        tc.cast_segment_world((1.0, f32::INFINITY), (2.0, 2.0))
+            .err(),
+        Some(CrissCrossError::NonFiniteCoordinates {
+            x: 1.0,
+            y: f32::INFINITY
+        })
+    );
+    let wc = WorldCoords::new(f32::NEG_INFINITY, 1.0, 1.0);
+    assert!(tc.cast_ray_coords(&wc, 0.0).is_err());
+}
@@ -0,0 +1,61 @@
+mod common;
+use crisscross::{BitGrid, FovAlgorithm, Grid, TilePosition, TileRaycaster};
+
+#[test]
+fn field_of_view_in_room() {
+    // Room with walls around the edges and a pillar at (3, 3)
+    let grid = Grid::new(8, 6, 1.0).unwrap();
+    let mut map = BitGrid::new(&grid);
+    for x in 0..grid.cols {
+        map.set(x, 0, true);
+        map.set(x, grid.rows - 1, true);
+    }
+    for y in 0..grid.rows {
+        map.set(0, y, true);
+        map.set(grid.cols - 1, y, true);
+    }
+    map.set(3, 3, true);
+
+    let tc = TileRaycaster::with_map(grid, map);
+    let origin = TilePosition::new(1, 3, 0.5, 0.5);
+    let visible = tc.field_of_view_blocked(&origin, 20.0).unwrap();
+
+    // Walls are visible
+    assert!(visible.get(0, 3));
+    assert!(visible.get(3, 3));
+    assert!(visible.get(7, 1));
+    // Tiles straight behind the pillar are not
+    assert!(!visible.get(4, 3));
+    assert!(!visible.get(5, 3));
+    // Tiles beside the pillar are
+    assert!(visible.get(5, 2));
+    assert!(visible.get(5, 4));
+
+    let same = tc
+        .field_of_view(&origin, 20.0, |tp| tc.map().get(tp.x, tp.y))
+        .unwrap();
+    assert_eq!(visible, same);
+}
+
+#[test]
+fn shadowcasting_agrees_between_observers() {
+    let grid = Grid::new(10, 10, 1.0).unwrap();
+    let mut map = BitGrid::new(&grid);
+    for (x, y) in vec![(4, 4), (4, 5), (6, 2), (2, 7)] {
+        map.set(x, y, true);
+    }
+    let tc = TileRaycaster::with_map(grid, map);
+
+    let player = TilePosition::new(1, 1, 0.5, 0.5);
+    let enemy = TilePosition::new(8, 7, 0.5, 0.5);
+    let player_fov = tc
+        .field_of_view_with_blocked(&player, 15.0, FovAlgorithm::Shadowcasting)
+        .unwrap();
+    let enemy_fov = tc
+        .field_of_view_with_blocked(&enemy, 15.0, FovAlgorithm::Shadowcasting)
+        .unwrap();
+    assert_eq!(
+        player_fov.get(enemy.x, enemy.y),
+        enemy_fov.get(player.x, player.y)
+    );
+}
@@ -0,0 +1,126 @@
+mod common;
+use common::round;
+use crisscross::{CrissCrossError, Crossing, HexGrid, HexPosition, HexRaycaster};
+
+fn round_hp(hp: HexPosition) -> (i32, i32, f32, f32) {
+    (hp.q, hp.r, round(hp.rel_x, 3), round(hp.rel_y, 3))
+}
+
+fn cast(tc: &HexRaycaster, hp: &HexPosition, angle: f32) -> Vec<(i32, i32, f32, f32)> {
+    tc.cast_ray(hp, angle.to_radians())
+        .unwrap()
+        .map(round_hp)
+        .collect()
+}
+
+#[test]
+fn cast_ray_radius_2() {
+    let tc = HexRaycaster::new(HexGrid::new(1.0, 2).unwrap());
+
+    // crosses edges at their centers
+    assert_eq!(
+        cast(&tc, &HexPosition::center(0, 0), 0.0),
+        [(1, 0, -0.866, 0.0), (2, 0, -0.866, 0.0)]
+    );
+    assert_eq!(
+        cast(&tc, &HexPosition::center(0, 0), 180.0),
+        [(-1, 0, 0.866, 0.0), (-2, 0, 0.866, 0.0)]
+    );
+
+    // zig zags upwards
+    assert_eq!(
+        cast(&tc, &HexPosition::new(0, 0, 0.2, 0.0), 90.0),
+        [(0, 1, -0.666, -0.615), (-1, 2, 0.2, -0.885)]
+    );
+
+    assert_eq!(
+        cast(&tc, &HexPosition::center(-2, 1), 10.0),
+        [
+            (-1, 1, -0.866, 0.153),
+            (0, 1, -0.866, 0.458),
+            (0, 2, -0.35, -0.798),
+            (1, 1, -0.209, 0.879)
+        ]
+    );
+}
+
+#[test]
+fn cast_ray_direction_vector_radius_2() {
+    let tc = HexRaycaster::new(HexGrid::new(1.0, 2).unwrap());
+    let hp = HexPosition::new(0, 0, 0.1, -0.2);
+    let by_vector: Vec<(i32, i32, f32, f32)> = tc
+        .cast_ray(&hp, (-2.0, 1.0))
+        .unwrap()
+        .map(round_hp)
+        .collect();
+    assert_eq!(
+        by_vector,
+        cast(&tc, &hp, (1.0_f32).atan2(-2.0).to_degrees())
+    );
+}
+
+#[test]
+fn cast_ray_ranged_radius_2() {
+    let tc = HexRaycaster::new(HexGrid::new(1.0, 2).unwrap());
+    let hps: Vec<_> = tc
+        .cast_ray_ranged(&HexPosition::center(0, 0), 0.0, 2.0)
+        .unwrap()
+        .map(round_hp)
+        .collect();
+    assert_eq!(hps, [(1, 0, -0.866, 0.0), (1, 0, 0.268, 0.0)]);
+}
+
+#[test]
+fn predicates_radius_2() {
+    let tc = HexRaycaster::new(HexGrid::new(1.0, 2).unwrap());
+    let origin = HexPosition::center(-2, 1);
+    let angle = 10_f32.to_radians();
+    let is_valid = |hp: &HexPosition| !(hp.q == 0 && hp.r == 2);
+
+    assert_eq!(
+        tc.last_valid(&origin, angle, is_valid)
+            .unwrap()
+            .map(round_hp),
+        Some((0, 1, -0.866, 0.458))
+    );
+    assert_eq!(
+        tc.first_invalid(&origin, angle, is_valid)
+            .unwrap()
+            .map(round_hp),
+        Some((0, 2, -0.35, -0.798))
+    );
+
+    let Crossing { valid, invalid } = tc.crossing(&origin, angle, is_valid).unwrap();
+    assert_eq!(valid.map(round_hp), Some((0, 1, -0.866, 0.458)));
+    assert_eq!(invalid.map(round_hp), Some((0, 2, -0.35, -0.798)));
+}
+
+#[test]
+fn positions() {
+    let grid = HexGrid::new(2.0, 1).unwrap();
+    assert!(grid.contains_hex(1, -1));
+    assert!(!grid.contains_hex(1, 1));
+    assert_eq!(
+        grid.hex_position_at(2.5, 3.0).map(round_hp),
+        Some((0, 1, 0.768, 0.0))
+    );
+    assert_eq!(grid.hex_position_at(10.0, 0.0), None);
+
+    // Inside of the circumcircle, but beyond the right edge of the hex
+    let unit = HexGrid::new(1.0, 1).unwrap();
+    assert!(!unit.contains(&HexPosition::new(0, 0, 0.95, 0.0)));
+    assert!(!unit.contains(&HexPosition::new(0, 0, 0.5, 0.8)));
+    assert!(unit.contains(&HexPosition::new(0, 0, 0.866, 0.0)));
+    assert!(unit.contains(&HexPosition::new(0, 0, 0.0, 1.0)));
+    assert!(unit.contains(&HexPosition::new(1, -1, -0.4, 0.7)));
+
+    let tc = HexRaycaster::new(grid);
+    assert_eq!(
+        tc.cast_ray(&HexPosition::center(2, 0), 0.0).err(),
+        Some(CrissCrossError::HexOutOfBounds { q: 2, r: 0 })
+    );
+    assert_eq!(
+        tc.cast_ray(&HexPosition::new(0, 0, 1.9, 0.0), 0.0).err(),
+        Some(CrissCrossError::HexOutOfBounds { q: 0, r: 0 })
+    );
+}
@@ -1,15 +1,18 @@
 mod common;
-use common::{round_beam_intersect, round_cutoff, round_tp};
-use crisscross::{BeamIntersect, Crossing, Grid, TilePosition, TileRaycaster};
+use common::{round, round_beam_intersect, round_cutoff, round_tp};
+use crisscross::{
+    BeamCrossing, BeamHit, BeamIntersect, Crossing, Grid, TilePosition, TileRaycaster,
+};
 
 #[test]
 fn last_valid() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
 
     assert_eq!(
         tc.last_valid(&((0, 0.0), (0, 0.0)).into(), 30_f32.to_radians(), |tp| {
             tp.y < 2
         })
+        .unwrap()
         .map(round_tp),
         Some(((3, 0.000), (1, 0.732)).into()),
     );
@@ -17,12 +20,13 @@ fn last_valid() {
 
 #[test]
 fn first_invalid() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
 
     assert_eq!(
         tc.first_invalid(&((0, 0.0), (0, 0.0)).into(), 45_f32.to_radians(), |tp| {
             tp.x < 2 && tp.y < 2
         })
+        .unwrap()
         .map(round_tp),
         Some(TilePosition {
             x: 2,
@@ -36,6 +40,7 @@ fn first_invalid() {
         tc.first_invalid(&((0, 0.0), (0, 0.0)).into(), 45_f32.to_radians(), |tp| {
             tp.x < 1 && tp.y < 1
         })
+        .unwrap()
         .map(round_tp),
         Some(TilePosition {
             x: 1,
@@ -48,9 +53,10 @@ fn first_invalid() {
 
 #[test]
 fn beam_last_valid() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
     let beam_width = 2.0;
 
+    // The rays right of the center originate below the grid and enter it through its bottom edge
     assert_eq!(
         tc.beam_last_valid(
             &((0, 0.0), (0, 0.0)).into(),
@@ -58,19 +64,75 @@ fn beam_last_valid() {
             30_f32.to_radians(),
             |BeamIntersect(_, tp)| { tp.y < 2 }
         )
+        .unwrap()
         .map(round_beam_intersect),
-        Some(BeamIntersect(0, ((3, 0.000), (1, 0.732)).into()))
+        Some(BeamIntersect(4, ((2, 0.000), (0, 0.000)).into()))
+    );
+}
+
+#[test]
+fn cone_last_valid() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    assert_eq!(
+        tc.cone_last_valid(
+            &((0, 0.5), (0, 0.5)).into(),
+            60_f32.to_radians(),
+            30_f32.to_radians(),
+            |BeamIntersect(_, tp)| { tp.y < 2 }
+        )
+        .unwrap()
+        .map(round_beam_intersect),
+        Some(BeamIntersect(9, ((1, 0.000), (1, 0.000)).into()))
+    );
+}
+
+#[test]
+fn beam_crossing() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let BeamCrossing { crossings, nearest } = tc
+        .beam_crossing(
+            &((0, 0.5), (0, 0.5)).into(),
+            1.0,
+            30_f32.to_radians(),
+            |BeamIntersect(_, tp)| tp.y < 2,
+        )
+        .unwrap();
+    let crossings: Vec<Crossing> = crossings.into_iter().map(round_cutoff).collect();
+    // The rightmost ray leaves the grid before reaching the third row
+    assert_eq!(
+        crossings,
+        [
+            Crossing {
+                valid: Some(((2, 0.000), (1, 0.943)).into()),
+                invalid: Some(((2, 0.098), (2, 0.000)).into()),
+            },
+            Crossing {
+                valid: Some(((3, 0.000), (1, 0.943)).into()),
+                invalid: Some(((3, 0.098), (2, 0.000)).into()),
+            },
+            Crossing {
+                valid: Some(((3, 0.000), (1, 0.366)).into()),
+                invalid: None,
+            }
+        ],
+    );
+    // The leftmost ray reaches the third row first
+    let BeamHit(idx, hit) = nearest.unwrap();
+    assert_eq!(
+        (idx, round_tp(hit.tp), round(hit.distance, 3)),
+        (0, ((2, 0.098), (2, 0.000)).into(), 2.134)
     );
 }
 
 #[test]
 fn cutoff() {
-    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0));
-    let cutoff = round_cutoff(tc.crossing(
-        &((0, 0.0), (0, 0.0)).into(),
-        30_f32.to_radians(),
-        |tp| tp.y < 2,
-    ));
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let cutoff = round_cutoff(
+        tc.crossing(&((0, 0.0), (0, 0.0)).into(), 30_f32.to_radians(), |tp| {
+            tp.y < 2
+        })
+        .unwrap(),
+    );
 
     assert_eq!(
         cutoff,
@@ -82,35 +144,76 @@ fn cutoff() {
 
     let tp_0_0: TilePosition = ((0, 0.0), (0, 0.0)).into();
     assert_eq!(
-        round_cutoff(tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 0)),
+        round_cutoff(
+            tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 0)
+                .unwrap()
+        ),
         Crossing::default(),
     );
     assert_eq!(
-        round_cutoff(tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 1)),
+        round_cutoff(
+            tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 1)
+                .unwrap()
+        ),
         Crossing {
             valid: Some(((1, 0.000), (0, 0.000)).into()),
             invalid: Some(((2, 0.000), (0, 0.000)).into())
         }
     );
     assert_eq!(
-        round_cutoff(tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 2)),
+        round_cutoff(
+            tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 2)
+                .unwrap()
+        ),
         Crossing {
             valid: Some(((2, 0.000), (0, 0.000)).into()),
             invalid: Some(((3, 0.000), (0, 0.000)).into())
         }
     );
     assert_eq!(
-        round_cutoff(tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 3)),
+        round_cutoff(
+            tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 3)
+                .unwrap()
+        ),
         Crossing {
             valid: Some(((3, 0.000), (0, 0.000)).into()),
             invalid: None,
         }
     );
     assert_eq!(
-        round_cutoff(tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 4)),
+        round_cutoff(
+            tc.crossing(&tp_0_0, 0_f32.to_radians(), |tp| tp.x <= 4)
+                .unwrap()
+        ),
         Crossing {
             valid: Some(((3, 0.000), (0, 0.000)).into()),
             invalid: None,
         }
     );
 }
+
+#[test]
+fn line_of_sight() {
+    let tc = TileRaycaster::new(Grid::new(4, 4, 1.0).unwrap());
+    let is_blocking = |tp: &TilePosition| tp.x == 2 && tp.y == 1;
+    let from: TilePosition = ((0, 0.5), (1, 0.5)).into();
+
+    assert!(!tc
+        .has_line_of_sight(&from, &((3, 0.5), (1, 0.5)).into(), is_blocking)
+        .unwrap());
+    assert!(tc
+        .has_line_of_sight(&from, &((3, 0.5), (3, 0.5)).into(), is_blocking)
+        .unwrap());
+    // the blocking tile itself can be seen
+    assert!(tc
+        .has_line_of_sight(&from, &((2, 0.5), (1, 0.5)).into(), is_blocking)
+        .unwrap());
+    // tiles past a target on the edge of its tile aren't checked
+    assert!(tc
+        .has_line_of_sight(
+            &((3, 0.5), (1, 0.5)).into(),
+            &((2, 0.0), (1, 0.5)).into(),
+            |tp| tp.x == 1
+        )
+        .unwrap());
+}
@@ -0,0 +1,223 @@
+mod common;
+use std::f32::consts::FRAC_PI_2;
+
+use crisscross::{Direction, Grid, RayHit, TilePosition, TileRaycaster};
+
+// Angles this far off each axis are swept in addition to the regular steps
+const NEAR_AXIS_OFFSETS: [f32; 6] = [1e-7, 1e-6, 1e-5, 1e-4, 1e-3, 1e-2];
+
+// Maximum distance of an intersection from the point on the ray at its reported distance
+const TOLERANCE: f32 = 1e-3;
+
+#[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+fn swept_angles() -> Vec<f32> {
+    let regular = (0..720).map(|idx| (idx as f32 * 0.5).to_radians());
+    let near_axis = (0..4).flat_map(|quarter| {
+        let axis = quarter as f32 * FRAC_PI_2;
+        NEAR_AXIS_OFFSETS
+            .iter()
+            .flat_map(move |offset| vec![axis + offset, axis - offset])
+    });
+    regular.chain(near_axis).collect()
+}
+
+fn origins() -> Vec<TilePosition> {
+    vec![
+        ((3, 0.5), (3, 0.5)).into(),
+        ((0, 0.0), (0, 0.0)).into(),
+        ((5, 0.0), (5, 0.0)).into(),
+        ((2, 0.0), (4, 0.5)).into(),
+        ((4, 0.25), (1, 0.9)).into(),
+        ((5, 0.999), (2, 0.001)).into(),
+    ]
+}
+
+fn grids() -> Vec<Grid> {
+    vec![
+        Grid::new(7, 7, 1.0).unwrap(),
+        Grid::new(7, 7, (2.0, 0.5)).unwrap(),
+        Grid::new(31, 31, 0.3).unwrap(),
+        Grid::new(31, 31, (0.7, 0.1)).unwrap(),
+    ]
+}
+
+/// Scales the relative offsets of a position on a grid with square tiles of size `1.0` to the
+/// tile size of the grid.
+fn on_grid(grid: &Grid, tp: &TilePosition) -> TilePosition {
+    let TilePosition { x, y, rel_x, rel_y } = tp.clone();
+    TilePosition::new(
+        x,
+        y,
+        rel_x * grid.tile_size.width,
+        rel_y * grid.tile_size.height,
+    )
+}
+
+#[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+fn world(grid: &Grid, tp: &TilePosition) -> (f32, f32) {
+    (
+        (tp.x as f32).mul_add(grid.tile_size.width, tp.rel_x),
+        (tp.y as f32).mul_add(grid.tile_size.height, tp.rel_y),
+    )
+}
+
+/// Distance along the ray from `(x, y)` at which it leaves the grid.
+#[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+fn distance_to_edge(grid: &Grid, (x, y): (f32, f32), (cos, sin): (f32, f32)) -> f32 {
+    let along = |start: f32, delta: f32, size: f32| {
+        if delta > 0.0 {
+            (size - start) / delta
+        } else if delta < 0.0 {
+            -start / delta
+        } else {
+            f32::INFINITY
+        }
+    };
+    along(x, cos, grid.width as f32).min(along(y, sin, grid.height as f32))
+}
+
+fn assert_contiguous(grid: &Grid, origin: &TilePosition, direction: &Direction, hits: &[RayHit]) {
+    let context = format!(
+        "{:?} from {:?} towards {:?}",
+        grid.tile_size, origin, direction
+    );
+    let (cos, sin) = (direction.x(), direction.y());
+    let (ox, oy) = world(grid, origin);
+
+    let mut previous = origin.clone();
+    let mut previous_distance = None;
+    for hit in hits {
+        let (dx, dy) = (
+            i64::from(hit.tp.x) - i64::from(previous.x),
+            i64::from(hit.tp.y) - i64::from(previous.y),
+        );
+        assert!(
+            dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0),
+            "skipped or repeated tile entering {:?} after {:?}, {}",
+            hit.tp,
+            previous,
+            context
+        );
+        assert!(
+            dx == 0 || (dx > 0) == (cos > 0.0),
+            "moved against the ray entering {:?}, {}",
+            hit.tp,
+            context
+        );
+        assert!(
+            dy == 0 || (dy > 0) == (sin > 0.0),
+            "moved against the ray entering {:?}, {}",
+            hit.tp,
+            context
+        );
+        if let Some(previous_distance) = previous_distance {
+            assert!(
+                hit.distance > previous_distance,
+                "distance didn't increase entering {:?}, {}",
+                hit.tp,
+                context
+            );
+        }
+        let (x, y) = world(grid, &hit.tp);
+        let (expected_x, expected_y) =
+            (cos.mul_add(hit.distance, ox), sin.mul_add(hit.distance, oy));
+        assert!(
+            (x - expected_x).abs() < TOLERANCE && (y - expected_y).abs() < TOLERANCE,
+            "{:?} isn't located on the ray, {}",
+            hit.tp,
+            context
+        );
+        previous = hit.tp.clone();
+        previous_distance = Some(hit.distance);
+    }
+
+    // The ray only stops once it leaves the grid, thus the point where it does so needs to be
+    // located in the last tile
+    let exit = distance_to_edge(grid, (ox, oy), (cos, sin));
+    let (exit_x, exit_y) = (cos.mul_add(exit, ox), sin.mul_add(exit, oy));
+    let (x, y) = world(grid, &TilePosition::new(previous.x, previous.y, 0.0, 0.0));
+    let (width, height) = (grid.tile_size.width, grid.tile_size.height);
+    assert!(
+        (x - TOLERANCE..=x + width + TOLERANCE).contains(&exit_x)
+            && (y - TOLERANCE..=y + height + TOLERANCE).contains(&exit_y),
+        "stopped in {:?} before leaving the grid at ({}, {}), {}",
+        previous,
+        exit_x,
+        exit_y,
+        context
+    );
+}
+
+#[test]
+fn rays_cross_contiguous_tiles() {
+    for grid in grids() {
+        let tc = TileRaycaster::new(grid.clone());
+        for origin in origins().iter().map(|tp| on_grid(&grid, tp)) {
+            for angle in swept_angles() {
+                let hits: Vec<RayHit> = tc.cast_ray(&origin, angle).unwrap().hits().collect();
+                assert_contiguous(&grid, &origin, &angle.into(), &hits);
+            }
+        }
+    }
+}
+
+#[test]
+fn direction_vectors_cross_contiguous_tiles() {
+    let grid = Grid::new(7, 7, 1.0).unwrap();
+    let tc = TileRaycaster::new(grid.clone());
+    let origin = TilePosition::new(3, 3, 0.5, 0.5);
+    for (dx, dy) in vec![
+        (1.0, 0.0),
+        (0.0, 1.0),
+        (-1.0, 0.0),
+        (0.0, -1.0),
+        (1.0, 1e-7),
+        (-1e-7, 1.0),
+        (1e-6, -1.0),
+        (-1.0, -1e-6),
+        (3.0, -4.0),
+    ] {
+        let hits: Vec<RayHit> = tc.cast_ray(&origin, (dx, dy)).unwrap().hits().collect();
+        assert_contiguous(&grid, &origin, &Direction::new(dx, dy), &hits);
+    }
+}
+
+#[test]
+fn wrapping_rays_cross_contiguous_tiles() {
+    let grid = Grid::new(5, 4, 1.0).unwrap().with_wrapping();
+    let tc = TileRaycaster::new(grid.clone());
+    let origin = TilePosition::new(2, 1, 0.3, 0.6);
+    // Steps across the edge of the grid continue on the opposite side
+    let wrapped = |delta: i64, count: i64| match delta.rem_euclid(count) {
+        1 => 1,
+        step if step == count - 1 => -1,
+        step => step,
+    };
+    for angle in swept_angles() {
+        let direction = Direction::from(angle);
+        let tiles: Vec<TilePosition> = tc.cast_ray_ranged(&origin, angle, 20.0).unwrap().collect();
+        let mut previous = origin.clone();
+        for (idx, tp) in tiles.iter().enumerate() {
+            let dx = wrapped(i64::from(tp.x) - i64::from(previous.x), 5);
+            let dy = wrapped(i64::from(tp.y) - i64::from(previous.y), 4);
+            // Only the end of the ray may lie inside the tile of the previous crossing
+            let is_end = idx + 1 == tiles.len();
+            assert!(
+                dx.abs() <= 1 && dy.abs() <= 1 && (is_end || (dx, dy) != (0, 0)),
+                "skipped or repeated tile entering {:?} after {:?} at {} rad",
+                tp,
+                previous,
+                angle
+            );
+            assert!(
+                (dx == 0 || (dx > 0) == (direction.x() > 0.0))
+                    && (dy == 0 || (dy > 0) == (direction.y() > 0.0)),
+                "moved against the ray entering {:?} after {:?} at {} rad",
+                tp,
+                previous,
+                angle
+            );
+            previous = tp.clone();
+        }
+    }
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "plot")]
+use crisscross::{
+    canvas::{BLUE, DARK_GRAY, GRAY, RED},
+    render::{Renderer, Surface},
+    Camera, Grid, TilePosition,
+};
+use image::{Rgb, RgbImage};
+
+const WIDTH: u32 = 32;
+const HEIGHT: u32 = 24;
+
+fn room(grid: &Grid, tp: &TilePosition) -> bool {
+    tp.x == 0 || tp.y == 0 || tp.x == grid.cols - 1 || tp.y == grid.rows - 1
+}
+
+#[test]
+fn solid_walls() {
+    let grid = Grid::new(5, 5, 1.0).unwrap();
+    let wall = Surface::Solid(RED);
+    let camera = Camera::new(TilePosition::new(2, 2, 0.5, 0.5), 0.0, 60_f32.to_radians());
+    let image = Renderer::new(WIDTH, HEIGHT).render(&grid, &camera, |tp| {
+        if room(&grid, tp) {
+            Some(&wall)
+        } else {
+            None
+        }
+    });
+
+    assert_eq!((image.width(), image.height()), (WIDTH, HEIGHT));
+    for x in 0..WIDTH {
+        assert_eq!(image.get_pixel(x, 0), &DARK_GRAY, "ceiling at {}", x);
+        assert_eq!(image.get_pixel(x, HEIGHT / 2), &RED, "wall at {}", x);
+        assert_eq!(image.get_pixel(x, HEIGHT - 1), &GRAY, "floor at {}", x);
+    }
+
+    // The flat wall ahead has the same height in every column, 32 / 2 / tan(30°) / 1.5
+    // = 18.475 pixels
+    for x in 0..WIDTH {
+        let wall_pixels = (0..HEIGHT)
+            .filter(|y| image.get_pixel(x, *y) == &RED)
+            .count();
+        assert_eq!(wall_pixels, 18, "column {}", x);
+    }
+}
+
+#[test]
+fn horizontal_faces_are_shaded() {
+    let grid = Grid::new(5, 5, 1.0).unwrap();
+    let wall = Surface::Solid(RED);
+    let camera = Camera::new(
+        TilePosition::new(2, 2, 0.5, 0.5),
+        90_f32.to_radians(),
+        60_f32.to_radians(),
+    );
+    let image = Renderer::new(WIDTH, HEIGHT).render(&grid, &camera, |tp| {
+        if room(&grid, tp) {
+            Some(&wall)
+        } else {
+            None
+        }
+    });
+    assert_eq!(image.get_pixel(WIDTH / 2, HEIGHT / 2), &Rgb([0x7f, 0, 0]));
+}
+
+#[test]
+fn textured_walls() {
+    let grid = Grid::new(5, 5, 1.0).unwrap();
+    // Left half red and right half blue
+    let texture = RgbImage::from_fn(2, 2, |x, _| if x == 0 { RED } else { BLUE });
+    let wall = Surface::Texture(texture);
+    // Looking at the center of the wall tile (4, 2) with a narrow field of view
+    let camera = Camera::new(TilePosition::new(2, 2, 0.5, 0.5), 0.0, 10_f32.to_radians());
+    let image = Renderer::new(WIDTH, HEIGHT).render(&grid, &camera, |tp| {
+        if room(&grid, tp) {
+            Some(&wall)
+        } else {
+            None
+        }
+    });
+
+    let middle = HEIGHT / 2;
+    assert_eq!(image.get_pixel(0, middle), &RED);
+    assert_eq!(image.get_pixel(WIDTH / 2 - 1, middle), &RED);
+    assert_eq!(image.get_pixel(WIDTH / 2, middle), &BLUE);
+    assert_eq!(image.get_pixel(WIDTH - 1, middle), &BLUE);
+}
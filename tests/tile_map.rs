@@ -0,0 +1,112 @@
+mod common;
+use common::{round, round_beam_intersect, round_cutoff, round_tp};
+use crisscross::{
+    BeamCrossing, BeamHit, BeamIntersect, BitGrid, Crossing, Grid, TilePosition, TileRaycaster,
+};
+
+fn raycaster_with_wall_at_row(row: u32) -> TileRaycaster<BitGrid> {
+    let grid = Grid::new(4, 4, 1.0).unwrap();
+    let mut map = BitGrid::new(&grid);
+    for x in 0..grid.cols {
+        map.set(x, row, true);
+    }
+    TileRaycaster::with_map(grid, map)
+}
+
+#[test]
+fn first_invalid_and_last_valid_blocked() {
+    let tc = raycaster_with_wall_at_row(2);
+    let origin: TilePosition = ((0, 0.0), (0, 0.0)).into();
+    let angle = 30_f32.to_radians();
+
+    assert_eq!(
+        tc.last_valid_blocked(&origin, angle).unwrap().map(round_tp),
+        Some(((3, 0.000), (1, 0.732)).into()),
+    );
+    assert_eq!(
+        tc.first_invalid_blocked(&origin, angle)
+            .unwrap()
+            .map(round_tp),
+        Some(((3, 0.464), (2, 0.000)).into()),
+    );
+    assert_eq!(
+        round_cutoff(tc.crossing_blocked(&origin, angle).unwrap()),
+        Crossing {
+            valid: Some(((3, 0.000), (1, 0.732)).into()),
+            invalid: Some(((3, 0.464), (2, 0.000)).into()),
+        }
+    );
+}
+
+#[test]
+fn beam_last_valid_blocked() {
+    let tc = raycaster_with_wall_at_row(2);
+    // The rays right of the center enter the grid through its bottom edge
+    assert_eq!(
+        tc.beam_last_valid_blocked(&((0, 0.0), (0, 0.0)).into(), 2.0, 30_f32.to_radians())
+            .unwrap()
+            .map(round_beam_intersect),
+        Some(BeamIntersect(4, ((2, 0.000), (0, 0.000)).into()))
+    );
+}
+
+#[test]
+fn beam_crossing_blocked() {
+    let tc = raycaster_with_wall_at_row(2);
+    let BeamCrossing { crossings, nearest } = tc
+        .beam_crossing_blocked(&((1, 0.5), (0, 0.5)).into(), 0.8, 90_f32.to_radians())
+        .unwrap();
+    let crossings: Vec<Crossing> = crossings.into_iter().map(round_cutoff).collect();
+    assert_eq!(
+        crossings,
+        [
+            Crossing {
+                valid: Some(((1, 0.100), (1, 0.000)).into()),
+                invalid: Some(((1, 0.100), (2, 0.000)).into()),
+            },
+            Crossing {
+                valid: Some(((1, 0.500), (1, 0.000)).into()),
+                invalid: Some(((1, 0.500), (2, 0.000)).into()),
+            },
+            Crossing {
+                valid: Some(((1, 0.900), (1, 0.000)).into()),
+                invalid: Some(((1, 0.900), (2, 0.000)).into()),
+            }
+        ],
+    );
+    // All rays reach the wall at the same distance, the first of them is reported
+    let BeamHit(idx, hit) = nearest.unwrap();
+    assert_eq!(
+        (idx, round_tp(hit.tp), round(hit.distance, 3)),
+        (0, ((1, 0.100), (2, 0.000)).into(), 1.5)
+    );
+}
+
+#[test]
+fn cone_last_valid_blocked() {
+    let tc = raycaster_with_wall_at_row(2);
+    assert_eq!(
+        tc.cone_last_valid_blocked(
+            &((0, 0.5), (0, 0.5)).into(),
+            60_f32.to_radians(),
+            30_f32.to_radians()
+        )
+        .unwrap()
+        .map(round_beam_intersect),
+        Some(BeamIntersect(9, ((1, 0.000), (1, 0.000)).into()))
+    );
+}
+
+#[test]
+fn updating_map() {
+    let mut tc = raycaster_with_wall_at_row(2);
+    let origin: TilePosition = ((1, 0.5), (0, 0.5)).into();
+    let up = 90_f32.to_radians();
+    assert_eq!(
+        tc.first_invalid_blocked(&origin, up).unwrap().map(round_tp),
+        Some(((1, 0.500), (2, 0.000)).into()),
+    );
+
+    tc.map_mut().set(1, 2, false);
+    assert_eq!(tc.first_invalid_blocked(&origin, up).unwrap(), None);
+}
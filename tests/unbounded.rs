@@ -0,0 +1,118 @@
+mod common;
+use common::round_stp;
+use crisscross::{CrissCrossError, SignedTilePosition, UnboundedRaycaster};
+
+fn cast(origin: &SignedTilePosition, angle: f32, max_distance: f32) -> Vec<SignedTilePosition> {
+    UnboundedRaycaster::new(1.0)
+        .unwrap()
+        .cast_ray_ranged(origin, angle.to_radians(), max_distance)
+        .unwrap()
+        .map(round_stp)
+        .collect()
+}
+
+#[test]
+fn negative_tiles() {
+    // ends exactly on the edge of a tile
+    assert_eq!(
+        cast(&((-1, 0.5), (-3, 0.5)).into(), 0.0, 2.5),
+        [
+            ((0, 0.000), (-3, 0.500)).into(),
+            ((1, 0.000), (-3, 0.500)).into(),
+            ((2, 0.000), (-3, 0.500)).into()
+        ]
+    );
+
+    // passes through the corner and ends inside a tile
+    assert_eq!(
+        cast(&((0, 0.5), (0, 0.5)).into(), 225.0, 2.0),
+        [
+            ((-1, 1.000), (-1, 1.000)).into(),
+            ((-1, 0.086), (-1, 0.086)).into()
+        ]
+    );
+}
+
+#[test]
+fn far_from_origin() {
+    let x = 1_000_000_000;
+    assert_eq!(
+        cast(&((x, 0.5), (-x, 0.5)).into(), 90.0, 1.0),
+        [
+            ((x, 0.500), (-x + 1, 0.000)).into(),
+            ((x, 0.500), (-x + 1, 0.500)).into()
+        ]
+    );
+}
+
+#[test]
+fn long_ray() {
+    // enters column 3000 at y = 0.5 + 2999.5 / 4
+    let tc = UnboundedRaycaster::new(1.0).unwrap();
+    assert_eq!(
+        tc.first_blocking(&((0, 0.5), (0, 0.5)).into(), (4.0, 1.0), 5000.0, |stp| {
+            stp.x == 3000
+        })
+        .unwrap()
+        .map(round_stp),
+        Some(((3000, 0.000), (750, 0.375)).into())
+    );
+}
+
+#[test]
+fn stop_on_predicate() {
+    let tc = UnboundedRaycaster::new((2.0, 1.0)).unwrap();
+    let origin = ((0, 0.5), (7, 0.5)).into();
+    assert_eq!(
+        tc.first_blocking(&origin, 180_f32.to_radians(), 100.0, |stp| stp.x == -5)
+            .unwrap()
+            .map(round_stp),
+        Some(((-5, 2.000), (7, 0.500)).into())
+    );
+    assert_eq!(
+        tc.first_blocking(&origin, 180_f32.to_radians(), 5.0, |stp| stp.x == -5)
+            .unwrap(),
+        None
+    );
+
+    // the unranged ray never ends
+    assert_eq!(
+        tc.cast_ray(&origin, 0.0)
+            .unwrap()
+            .take_while(|stp| stp.x < 1_000)
+            .count(),
+        999
+    );
+}
+
+#[test]
+fn invalid_inputs() {
+    assert_eq!(
+        UnboundedRaycaster::new(-1.0).err(),
+        Some(CrissCrossError::InvalidTileSize {
+            width: -1.0,
+            height: -1.0
+        })
+    );
+    let tc = UnboundedRaycaster::new(1.0).unwrap();
+    assert!(tc.cast_ray(&((0, 0.5), (0, 0.5)).into(), f32::NAN).is_err());
+    assert_eq!(
+        tc.cast_ray(&((0, 0.5), (0, 0.5)).into(), (0.0, 0.0)).err(),
+        Some(CrissCrossError::InvalidDirection)
+    );
+}
+
+#[test]
+fn direction_vectors() {
+    let tc = UnboundedRaycaster::new(1.0).unwrap();
+    let origin: SignedTilePosition = ((0, 0.5), (0, 0.5)).into();
+    let by_angle: Vec<SignedTilePosition> = tc
+        .cast_ray_ranged(&origin, 45_f32.to_radians(), 3.0)
+        .unwrap()
+        .collect();
+    let by_vector: Vec<SignedTilePosition> = tc
+        .cast_ray_ranged(&origin, (f32::MAX, f32::MAX), 3.0)
+        .unwrap()
+        .collect();
+    assert_eq!(by_vector, by_angle);
+}
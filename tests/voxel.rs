@@ -0,0 +1,122 @@
+mod common;
+use common::round;
+use crisscross::{CrissCrossError, Crossing, VoxelGrid, VoxelPosition, VoxelRaycaster};
+
+type Rounded = ((u32, u32, u32), (f32, f32, f32));
+
+fn round_vp(vp: VoxelPosition) -> Rounded {
+    (
+        (vp.x, vp.y, vp.z),
+        (round(vp.rel_x, 3), round(vp.rel_y, 3), round(vp.rel_z, 3)),
+    )
+}
+
+fn cast(tc: &VoxelRaycaster, vp: &VoxelPosition, direction: (f32, f32, f32)) -> Vec<Rounded> {
+    tc.cast_ray(vp, direction).unwrap().map(round_vp).collect()
+}
+
+#[test]
+fn cast_ray_4x4x4() {
+    let tc = VoxelRaycaster::new(VoxelGrid::new(4, 4, 4, 1.0).unwrap());
+
+    let vp = VoxelPosition::new(1, 1, 1, 0.5, 0.5, 0.5);
+    assert_eq!(
+        cast(&tc, &vp, (1.0, 0.0, 0.0)),
+        [((2, 1, 1), (0.0, 0.5, 0.5)), ((3, 1, 1), (0.0, 0.5, 0.5))]
+    );
+
+    // passes through corners
+    let vp = VoxelPosition::new(0, 0, 0, 0.5, 0.5, 0.5);
+    assert_eq!(
+        cast(&tc, &vp, (1.0, 1.0, 1.0)),
+        [
+            ((1, 1, 1), (0.0, 0.0, 0.0)),
+            ((2, 2, 2), (0.0, 0.0, 0.0)),
+            ((3, 3, 3), (0.0, 0.0, 0.0))
+        ]
+    );
+
+    // passes through an edge while moving left
+    let vp = VoxelPosition::new(3, 0, 2, 0.5, 0.25, 0.5);
+    assert_eq!(
+        cast(&tc, &vp, (-1.0, 0.5, 0.0)),
+        [
+            ((2, 0, 2), (1.0, 0.5, 0.5)),
+            ((1, 1, 2), (1.0, 0.0, 0.5)),
+            ((0, 1, 2), (1.0, 0.5, 0.5))
+        ]
+    );
+}
+
+#[test]
+fn cast_ray_ranged_4x4x4() {
+    let tc = VoxelRaycaster::new(VoxelGrid::new(4, 4, 4, 1.0).unwrap());
+    let vp = VoxelPosition::new(1, 1, 1, 0.5, 0.5, 0.5);
+    let vps: Vec<Rounded> = tc
+        .cast_ray_ranged(&vp, (2.0, 0.0, 0.0), 1.2)
+        .unwrap()
+        .map(round_vp)
+        .collect();
+    assert_eq!(
+        vps,
+        [((2, 1, 1), (0.0, 0.5, 0.5)), ((2, 1, 1), (0.7, 0.5, 0.5))]
+    );
+}
+
+#[test]
+fn far_from_origin() {
+    let tc = VoxelRaycaster::new(VoxelGrid::new(u32::MAX, u32::MAX, 4, 1.0).unwrap());
+    let (x, y) = (4_000_000_000, 3_000_000_000);
+    let vp = VoxelPosition::new(x, y, 1, 0.5, 0.5, 0.5);
+    let vps: Vec<Rounded> = tc
+        .cast_ray_ranged(&vp, (0.0, 1.0, 0.0), 1.0)
+        .unwrap()
+        .map(round_vp)
+        .collect();
+    assert_eq!(
+        vps,
+        [
+            ((x, y + 1, 1), (0.5, 0.0, 0.5)),
+            ((x, y + 1, 1), (0.5, 0.5, 0.5))
+        ]
+    );
+}
+
+#[test]
+fn predicates_4x4x4() {
+    let tc = VoxelRaycaster::new(VoxelGrid::new(4, 4, 4, 1.0).unwrap());
+    let vp = VoxelPosition::new(3, 0, 2, 0.5, 0.25, 0.5);
+    let direction = (-1.0, 0.5, 0.0);
+    let is_valid = |vp: &VoxelPosition| vp.x != 1;
+
+    assert_eq!(
+        tc.last_valid(&vp, direction, is_valid)
+            .unwrap()
+            .map(round_vp),
+        Some(((2, 0, 2), (1.0, 0.5, 0.5)))
+    );
+    assert_eq!(
+        tc.first_invalid(&vp, direction, is_valid)
+            .unwrap()
+            .map(round_vp),
+        Some(((1, 1, 2), (1.0, 0.0, 0.5)))
+    );
+    let Crossing { valid, invalid } = tc.crossing(&vp, direction, is_valid).unwrap();
+    assert_eq!(valid.map(round_vp), Some(((2, 0, 2), (1.0, 0.5, 0.5))));
+    assert_eq!(invalid.map(round_vp), Some(((1, 1, 2), (1.0, 0.0, 0.5))));
+}
+
+#[test]
+fn invalid_inputs() {
+    let tc = VoxelRaycaster::new(VoxelGrid::new(4, 4, 4, 1.0).unwrap());
+    let vp = VoxelPosition::new(1, 1, 1, 0.5, 0.5, 0.5);
+    assert_eq!(
+        tc.cast_ray(&vp, (0.0, 0.0, 0.0)).err(),
+        Some(CrissCrossError::InvalidDirection)
+    );
+    assert_eq!(
+        tc.cast_ray(&VoxelPosition::new(1, 4, 1, 0.5, 0.5, 0.5), (1.0, 0.0, 0.0))
+            .err(),
+        Some(CrissCrossError::VoxelOutOfBounds { x: 1, y: 4, z: 1 })
+    );
+}
@@ -0,0 +1,92 @@
+mod common;
+use common::{round, round_tp};
+use crisscross::{CrissCrossError, Grid, TileRaycaster, WorldCoords};
+
+fn round_wc(wc: WorldCoords) -> (f32, f32) {
+    (round(wc.x(), 3), round(wc.y(), 3))
+}
+
+#[test]
+fn conversions() {
+    let grid = Grid::new(4, 4, (2.0, 1.0)).unwrap();
+    let wc = WorldCoords::new(3.5, 2.25, grid.tile_size);
+    assert_eq!(
+        wc.to_tile_position().map(round_tp),
+        Ok(((1, 1.5), (2, 0.25)).into())
+    );
+    assert_eq!(
+        wc.to_tile_position_in(&grid).map(round_tp),
+        Ok(((1, 1.5), (2, 0.25)).into())
+    );
+    assert_eq!(
+        WorldCoords::from_tile_position(&((1, 1.5), (2, 0.25)).into(), grid.tile_size),
+        wc
+    );
+
+    let wc = WorldCoords::new(-2.5, 1.0, grid.tile_size);
+    assert_eq!(
+        wc.to_tile_position(),
+        Err(CrissCrossError::NegativeTileCoordinates { x: -1, y: 1 })
+    );
+
+    let wc = WorldCoords::new(8.5, 1.0, grid.tile_size);
+    assert!(wc.to_tile_position().is_ok());
+    let err = wc.to_tile_position_in(&grid).unwrap_err();
+    assert_eq!(err, CrissCrossError::OutOfBounds { x: 8.5, y: 1.0 });
+    assert_eq!(
+        err.to_string(),
+        "world coordinates (8.5, 1) are outside of the grid"
+    );
+}
+
+#[test]
+fn cast_ray_coords() {
+    let grid = Grid::new(4, 4, 1.0).unwrap();
+    let tc = TileRaycaster::new(grid.clone());
+
+    let from = WorldCoords::new(0.0, 0.0, grid.tile_size);
+    assert_eq!(
+        tc.cast_ray_coords(&from, 30_f32.to_radians())
+            .unwrap()
+            .map(round_wc)
+            .collect::<Vec<_>>(),
+        [
+            (1.0, 0.577),
+            (1.732, 1.0),
+            (2.0, 1.155),
+            (3.0, 1.732),
+            (3.464, 2.0)
+        ],
+    );
+
+    let from = WorldCoords::new(-1.0, 1.5, grid.tile_size);
+    assert_eq!(
+        tc.cast_ray_coords(&from, 0.0)
+            .unwrap()
+            .map(round_wc)
+            .collect::<Vec<_>>(),
+        [(0.0, 1.5), (1.0, 1.5), (2.0, 1.5), (3.0, 1.5)],
+    );
+}
+
+#[test]
+fn cast_segment_coords() {
+    let grid = Grid::new(4, 4, 1.0).unwrap();
+    let tc = TileRaycaster::new(grid.clone());
+
+    let from = WorldCoords::new(0.5, 0.5, grid.tile_size);
+    let to = WorldCoords::new(2.5, 0.5, grid.tile_size);
+    assert_eq!(
+        tc.cast_segment_coords(&from, &to)
+            .unwrap()
+            .map(round_wc)
+            .collect::<Vec<_>>(),
+        [(1.0, 0.5), (2.0, 0.5), (2.5, 0.5)],
+    );
+
+    let to = WorldCoords::new(4.5, 0.5, grid.tile_size);
+    assert_eq!(
+        tc.cast_segment_coords(&from, &to).err(),
+        Some(CrissCrossError::OutOfBounds { x: 4.5, y: 0.5 })
+    );
+}